@@ -64,7 +64,7 @@ fn benchmark_transform(c: &mut Criterion) {
             Command::cargo_bin("dts")
                 .unwrap()
                 .arg("tests/fixtures")
-                .args(&["--glob", "*", "-C", "-j", ".[]"])
+                .args(["--glob", "*", "-C", "-j", ".[]"])
                 .assert()
                 .success()
         })
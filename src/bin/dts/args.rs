@@ -6,8 +6,14 @@ use crate::paging::PagingChoice;
 use anyhow::{anyhow, Result};
 use clap::{Args, Parser, ValueHint};
 use clap_complete::Shell;
-use dts::{de::DeserializeOptions, ser::SerializeOptions, Encoding, Sink, Source};
+use dts::{
+    de::{CsvTrim, DeserializeOptions},
+    ser::{CsvNewlinePolicy, GronQuote, SerializeOptions},
+    Encoding, Sink, Source,
+};
 use regex::Regex;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
 use unescape::unescape;
 
 /// Simple tool to transcode between different encodings.
@@ -43,6 +49,19 @@ pub struct Options {
     #[arg(short = 'O', long = "sink", value_name = "SINK", value_hint = ValueHint::FilePath)]
     pub sinks: Vec<Sink>,
 
+    /// Load default options from a config file.
+    ///
+    /// The config file is a JSON, YAML or TOML object which maps option names to values, e.g.
+    /// `{"output_encoding": "yaml", "compact": true}`. Option names are the long flag names (e.g.
+    /// `jq` or `sink`) with either `-` or `_` as word separator. Boolean flags take `true` or
+    /// `false`, options which can be specified multiple times take an array.
+    ///
+    /// Options provided on the command line take precedence over the config file, which takes
+    /// precedence over the built-in defaults. An option from the config file is also ignored if it
+    /// conflicts with an option provided on the command line.
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+    pub config: Option<PathBuf>,
+
     /// Options for deserializing the input.
     #[clap(flatten)]
     pub input: InputOptions,
@@ -56,6 +75,14 @@ pub struct Options {
     #[clap(flatten)]
     pub output: OutputOptions,
 
+    /// Disable automatic encoding detection.
+    ///
+    /// Input and output encodings are never inferred from file extensions, the first line of
+    /// input or the Content-Type of remote sources. Both `--input-encoding` and
+    /// `--output-encoding` must be provided explicitly.
+    #[arg(long, requires_all = ["input_encoding", "output_encoding"])]
+    pub no_detect: bool,
+
     /// If provided, outputs the completion file for the given shell.
     #[arg(value_enum, long, value_name = "SHELL", group = "generate-completion")]
     pub generate_completion: Option<Shell>,
@@ -72,8 +99,14 @@ pub struct InputOptions {
     /// Set the input encoding.
     ///
     /// If absent, dts will attempt to detect the encoding from the input file extension (if
-    /// present) or from the first line of input.
-    #[arg(value_enum, short = 'i', long, help_heading = "Input Options")]
+    /// present) or from the first line of input. Encoding names are case-insensitive.
+    #[arg(
+        value_enum,
+        short = 'i',
+        long,
+        ignore_case = true,
+        help_heading = "Input Options"
+    )]
     pub input_encoding: Option<Encoding>,
 
     /// Indicate that CSV input does not include a header row.
@@ -91,14 +124,56 @@ pub struct InputOptions {
     #[arg(short = 'H', long, help_heading = "Input Options")]
     pub csv_headers_as_keys: bool,
 
+    /// Use NAME as key for a column of CSV input rows. Can be specified multiple times.
+    ///
+    /// The names are assigned to the columns in order and each row is deserialized into a map.
+    /// Combine with --csv-without-headers if the input does not have a header row, otherwise the
+    /// header row is discarded. Excess fields of a row are ignored.
+    #[arg(
+        long = "csv-input-column",
+        value_name = "NAME",
+        help_heading = "Input Options"
+    )]
+    pub csv_input_columns: Vec<String>,
+
     /// Custom delimiter for CSV input.
     #[arg(short = 'd', long, value_parser = parse_csv_delimiter, help_heading = "Input Options")]
     pub csv_input_delimiter: Option<u8>,
 
+    /// Controls trimming of leading and trailing whitespace of CSV input fields.
+    #[arg(
+        value_enum,
+        long,
+        value_name = "WHAT",
+        default_value = "all",
+        help_heading = "Input Options"
+    )]
+    pub csv_trim: CsvTrim,
+
+    /// Accept CSV rows with varying numbers of fields.
+    ///
+    /// When rows are read into objects, missing fields are set to empty strings and excess fields
+    /// are dropped.
+    #[arg(long, help_heading = "Input Options")]
+    pub csv_flexible: bool,
+
     /// Regex pattern to split text input at.
     #[arg(short = 's', long, help_heading = "Input Options")]
     pub text_split_pattern: Option<Regex>,
 
+    /// Parse text input as fixed-width columns.
+    ///
+    /// Takes a comma separated list of column names and widths in characters, e.g.
+    /// `name:10,code:4`. Each line is sliced into an object with trimmed fields.
+    #[arg(
+        long = "fixed-width",
+        value_name = "NAME:WIDTH",
+        value_delimiter = ',',
+        value_parser = parse_fixed_width,
+        help_heading = "Input Options"
+    )]
+    pub fixed_widths: Vec<(String, usize)>,
+
     /// Glob pattern for directories.
     ///
     /// Required if any of the input paths is a directory. Ignored otherwise.
@@ -113,6 +188,14 @@ pub struct InputOptions {
     #[arg(short = 'P', long, help_heading = "Input Options")]
     pub file_paths: bool,
 
+    /// Wrap each input value together with its origin.
+    ///
+    /// If multiple input files or at least one directory is provided, each deserialized value is
+    /// wrapped into an object of the form `{"source": <path>, "data": <value>}`. This can be
+    /// combined with --file-paths. If only one input file is provided, this option is ignored.
+    #[arg(long, help_heading = "Input Options")]
+    pub with_source_meta: bool,
+
     /// Continue on errors that occur while reading or deserializing input data.
     ///
     /// If the flag is provided, `dts` will continue to read and deserialize the remaining input
@@ -123,6 +206,47 @@ pub struct InputOptions {
     #[arg(short = 'C', long, help_heading = "Input Options")]
     pub continue_on_error: bool,
 
+    /// Maximum number of input sources to read and deserialize in parallel.
+    ///
+    /// Defaults to the number of CPUs. Pass `1` to process input sources sequentially.
+    #[arg(long, value_name = "N", help_heading = "Input Options")]
+    pub jobs: Option<NonZeroUsize>,
+
+    /// Process a stream of JSON values one value at a time.
+    ///
+    /// Each value of the input is transformed individually by the jq expressions and written as a
+    /// compact JSON line to the output, e.g. for processing NDJSON files with constant memory.
+    /// Since the jq expressions never see all values at once, aggregations like `sort` or
+    /// `group_by` only operate on single values. Like without --stream, an expression that
+    /// produces multiple outputs for a value, e.g. `.items[]`, writes them as a single array line.
+    ///
+    /// Requires a single input source and at most one output sink, both using JSON encoding.
+    /// Options that operate on the output as a whole, like --atomic or --pretty, cannot be
+    /// combined with it.
+    #[arg(
+        long,
+        help_heading = "Input Options",
+        conflicts_with_all = [
+            "assert",
+            "atomic",
+            "diff_with",
+            "emit_schema",
+            "manifest",
+            "partition_by",
+            "pretty",
+            "shard_max_bytes",
+            "with_source_meta",
+        ]
+    )]
+    pub stream: bool,
+
+    /// Allow input that looks like binary data for text encodings.
+    ///
+    /// By default, input containing NUL bytes or invalid UTF-8 within its first few kilobytes is
+    /// rejected for all encodings except binary ones like plist.
+    #[arg(long, help_heading = "Input Options")]
+    pub allow_binary: bool,
+
     /// Simplify input if the encoding supports it.
     ///
     /// Some encodings like HCL support partial expression evaluation, where an expression like
@@ -130,6 +254,22 @@ pub struct InputOptions {
     /// should be performed or not.
     #[arg(long, help_heading = "Input Options")]
     pub simplify: bool,
+
+    /// Preserve YAML tags like `!Ref`.
+    ///
+    /// Tagged YAML values are deserialized into objects of the form `{"__tag__": "!Ref", "value":
+    /// <value>}` which are turned back into tagged values when serializing to YAML. Without this
+    /// flag, tags are dropped.
+    #[arg(long, help_heading = "Input Options")]
+    pub preserve_yaml_tags: bool,
+
+    /// Name of the sheet to read from Excel input.
+    ///
+    /// If absent, the first sheet of the workbook is read. The CSV header flags also apply to
+    /// Excel input.
+    #[cfg(feature = "excel")]
+    #[arg(long, value_name = "NAME", help_heading = "Input Options")]
+    pub sheet: Option<String>,
 }
 
 impl From<&InputOptions> for DeserializeOptions {
@@ -137,9 +277,17 @@ impl From<&InputOptions> for DeserializeOptions {
         Self {
             csv_headers_as_keys: opts.csv_headers_as_keys,
             csv_without_headers: opts.csv_without_headers,
+            csv_columns: (!opts.csv_input_columns.is_empty())
+                .then(|| opts.csv_input_columns.clone()),
             csv_delimiter: opts.csv_input_delimiter,
+            csv_trim: opts.csv_trim,
+            csv_flexible: opts.csv_flexible,
             text_split_pattern: opts.text_split_pattern.clone(),
+            fixed_widths: (!opts.fixed_widths.is_empty()).then(|| opts.fixed_widths.clone()),
             simplify: opts.simplify,
+            preserve_yaml_tags: opts.preserve_yaml_tags,
+            #[cfg(feature = "excel")]
+            xlsx_sheet: opts.sheet.clone(),
         }
     }
 }
@@ -153,6 +301,9 @@ pub struct TransformOptions {
     /// If the expression starts with an `@` it is treated as a local file path and the expression
     /// is read from there instead.
     ///
+    /// Can be specified multiple times. The expressions are applied in order, each one receiving
+    /// the result of the previous one as input.
+    ///
     /// See <https://stedolan.github.io/jq/manual/> for supported operators, filters and
     /// functions.
     #[arg(
@@ -161,7 +312,15 @@ pub struct TransformOptions {
         value_name = "EXPRESSION",
         help_heading = "Transform Options"
     )]
-    pub jq_expression: Option<String>,
+    pub jq_expressions: Vec<String>,
+
+    /// Print the value after each transformation step to stderr.
+    ///
+    /// Each line is labeled with the position and the jq expression of the step and contains the
+    /// intermediate value as compact JSON. This is useful for debugging chains of multiple --jq
+    /// expressions.
+    #[arg(long, help_heading = "Transform Options")]
+    pub trace_transforms: bool,
 }
 
 /// Options that configure the behaviour of data transformation.
@@ -177,6 +336,9 @@ pub struct TransformOptions {
     /// If the expression starts with an `@` it is treated as a local file path and the expression
     /// is read from there instead.
     ///
+    /// Can be specified multiple times. The expressions are applied in order, each one receiving
+    /// the result of the previous one as input.
+    ///
     /// See <https://stedolan.github.io/jq/manual/> for supported operators, filters and
     /// functions.
     #[arg(
@@ -185,7 +347,15 @@ pub struct TransformOptions {
         value_name = "EXPRESSION",
         help_heading = "Transform Options"
     )]
-    pub jq_expression: Option<String>,
+    pub jq_expressions: Vec<String>,
+
+    /// Print the value after each transformation step to stderr.
+    ///
+    /// Each line is labeled with the position and the jq expression of the step and contains the
+    /// intermediate value as compact JSON. This is useful for debugging chains of multiple --jq
+    /// expressions.
+    #[arg(long, help_heading = "Transform Options")]
+    pub trace_transforms: bool,
 }
 
 /// Options that configure the behaviour of output serialization.
@@ -196,8 +366,15 @@ pub struct OutputOptions {
     /// If absent, the encoding will be detected from the output file extension.
     ///
     /// If the encoding is not explicitly set and it cannot be inferred from the output file
-    /// extension (or the output is stdout), the fallback is to encode output as JSON.
-    #[arg(value_enum, short = 'o', long, help_heading = "Output Options")]
+    /// extension (or the output is stdout), the fallback is to encode output as JSON. Encoding
+    /// names are case-insensitive.
+    #[arg(
+        value_enum,
+        short = 'o',
+        long,
+        ignore_case = true,
+        help_heading = "Output Options"
+    )]
     pub output_encoding: Option<Encoding>,
 
     /// Controls when to use colors.
@@ -252,10 +429,24 @@ pub struct OutputOptions {
 
     /// Emit output data in a compact format.
     ///
-    /// This will disable pretty printing for encodings that support it.
-    #[arg(short = 'c', long, help_heading = "Output Options")]
+    /// This will disable pretty printing for encodings that support it (JSON, TOML and HCL).
+    ///
+    /// If neither --compact nor --pretty is provided, output is pretty printed when writing to a
+    /// terminal or to output files and compact when stdout is piped into another program.
+    #[arg(
+        short = 'c',
+        long,
+        conflicts_with = "pretty",
+        help_heading = "Output Options"
+    )]
     pub compact: bool,
 
+    /// Pretty print output data.
+    ///
+    /// This is the inverse of --compact, see its documentation for the default behaviour.
+    #[arg(short = 'p', long, help_heading = "Output Options")]
+    pub pretty: bool,
+
     /// Add a trailing newline to the output.
     #[arg(short = 'n', long, help_heading = "Output Options")]
     pub newline: bool,
@@ -273,23 +464,175 @@ pub struct OutputOptions {
     #[arg(short = 'D', long, value_parser = parse_csv_delimiter, help_heading = "Output Options")]
     pub csv_output_delimiter: Option<u8>,
 
+    /// Controls how newlines embedded in CSV output fields are handled.
+    ///
+    /// By default, fields containing newlines are quoted as required by RFC 4180. Since this can
+    /// break naive line-based parsers, newlines can also be escaped as `\n` or stripped.
+    #[arg(
+        value_enum,
+        long,
+        value_name = "POLICY",
+        default_value = "quote",
+        help_heading = "Output Options"
+    )]
+    pub csv_newline_policy: CsvNewlinePolicy,
+
     /// Custom separator to join text output with.
     #[arg(short = 'J', long, value_parser = parse_unescaped, help_heading = "Output Options")]
     pub text_join_separator: Option<String>,
 
+    /// Escape control characters in text output.
+    ///
+    /// Control characters other than newline and tab (e.g. bell or escape) are written as `\xNN`
+    /// so that they cannot mess with the terminal. Only applies to text output.
+    #[arg(long, help_heading = "Output Options")]
+    pub escape_control: bool,
+
+    /// Controls how string values are quoted in gron output.
+    ///
+    /// Only the default `json` style can be read back by dts. The `single` and `none` styles are
+    /// useful to produce shell-friendly output.
+    #[arg(
+        value_enum,
+        long,
+        value_name = "STYLE",
+        default_value = "json",
+        help_heading = "Output Options"
+    )]
+    pub gron_quote: GronQuote,
+
+    /// Emit canonical JSON according to the JSON Canonicalization Scheme (RFC 8785).
+    ///
+    /// Object keys are sorted, insignificant whitespace is removed and numbers are formatted in
+    /// their shortest form. This is useful to produce reproducible output for hashing or signing.
+    /// Only applies to JSON output.
+    #[arg(long, help_heading = "Output Options")]
+    pub jcs: bool,
+
+    /// Wrap output that is not a table under KEY when serializing to TOML.
+    ///
+    /// TOML documents must have a table as root. With this option, arrays and scalar values are
+    /// emitted as the value of KEY instead of producing an error, e.g. `items = [...]`.
+    #[arg(long, value_name = "KEY", help_heading = "Output Options")]
+    pub toml_root_key: Option<String>,
+
     /// Overwrite output files if they exist.
     #[arg(long)]
     pub overwrite: bool,
+
+    /// Write output files atomically.
+    ///
+    /// Output is written to a temporary file in the same directory first, which then replaces the
+    /// output file once serialization succeeded. This ensures that existing output files are left
+    /// untouched if an error occurs.
+    #[arg(long)]
+    pub atomic: bool,
+
+    /// Append to output files instead of overwriting them.
+    ///
+    /// This is mainly useful for line-oriented output like text or compact JSON, where repeated
+    /// runs accumulate data in the same file. Combine it with --newline to produce NDJSON.
+    #[arg(long, conflicts_with_all = ["atomic", "overwrite"])]
+    pub append: bool,
+
+    /// Compare the output against the contents of a file instead of writing it.
+    ///
+    /// For structured encodings the comparison is performed on the deserialized data, so
+    /// formatting differences are ignored. Text output is compared byte by byte. If the output
+    /// does not match, a diff is printed and dts exits with a non-zero status.
+    ///
+    /// Output sinks are ignored when this option is provided.
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath, help_heading = "Output Options")]
+    pub assert: Option<PathBuf>,
+
+    /// Print a unified diff between the contents of FILE and the output instead of writing it.
+    ///
+    /// Like --assert, the data is compared structurally unless the output encoding is text.
+    /// Nothing is printed if there are no differences. When colors are enabled, removed lines are
+    /// printed in red and added lines in green.
+    ///
+    /// Output sinks are ignored when this option is provided.
+    #[arg(
+        long,
+        value_name = "FILE",
+        value_hint = ValueHint::FilePath,
+        conflicts_with = "assert",
+        help_heading = "Output Options"
+    )]
+    pub diff_with: Option<PathBuf>,
+
+    /// Partition an array of objects into multiple output files by the value of a field.
+    ///
+    /// Each distinct field value produces a file in the directory given by --partition-dir which
+    /// contains an array of all records with that value. Files are named after the field value
+    /// with characters that are unsafe in file names replaced by `_`, and the output encoding as
+    /// extension. Records without the field end up in the `null` partition. It is an error if
+    /// distinct field values map to the same file name, e.g. `a/b` and `a?b` or `1` and `"1"`.
+    #[arg(
+        long,
+        value_name = "FIELD",
+        requires = "partition_dir",
+        conflicts_with = "sinks",
+        help_heading = "Output Options"
+    )]
+    pub partition_by: Option<String>,
+
+    /// Split array output into multiple files of at most BYTES bytes each.
+    ///
+    /// Requires exactly one output file. The shards are named after it with a numeric suffix
+    /// inserted before the extension, e.g. `out.json` is split into `out.0.json`, `out.1.json`
+    /// and so on. Each shard contains an array of consecutive elements. It is an error if a single
+    /// element does not fit into a shard.
+    #[arg(
+        long,
+        value_name = "BYTES",
+        requires = "sinks",
+        conflicts_with = "partition_by",
+        help_heading = "Output Options"
+    )]
+    pub shard_max_bytes: Option<usize>,
+
+    /// Directory to write partitioned output files into.
+    #[arg(
+        long,
+        value_name = "DIR",
+        value_hint = ValueHint::DirPath,
+        requires = "partition_by",
+        help_heading = "Output Options"
+    )]
+    pub partition_dir: Option<PathBuf>,
+
+    /// Write a manifest of all output files to FILE.
+    ///
+    /// The manifest is a JSON array with a `{"path": ..., "bytes": ..., "encoding": ...}` entry
+    /// for each output file written, including partitioned and sharded output. Output written to
+    /// stdout is not listed.
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath, help_heading = "Output Options")]
+    pub manifest: Option<PathBuf>,
+
+    /// Output a JSON Schema (draft 2020-12) describing the structure of the data instead of the
+    /// data itself.
+    #[arg(long, help_heading = "Output Options")]
+    pub emit_schema: bool,
 }
 
 impl From<&OutputOptions> for SerializeOptions {
     fn from(opts: &OutputOptions) -> Self {
         Self {
-            compact: opts.compact,
+            compact: match (opts.compact, opts.pretty) {
+                (false, false) => None,
+                (compact, _) => Some(compact),
+            },
             newline: opts.newline,
             keys_as_csv_headers: opts.keys_as_csv_headers,
             csv_delimiter: opts.csv_output_delimiter,
+            csv_newline_policy: opts.csv_newline_policy,
             text_join_separator: opts.text_join_separator.clone(),
+            text_escape_control: opts.escape_control,
+            jcs: opts.jcs,
+            gron_quote: opts.gron_quote,
+            toml_root_key: opts.toml_root_key.clone(),
+            preserve_yaml_tags: false,
         }
     }
 }
@@ -305,6 +648,53 @@ fn parse_csv_delimiter(s: &str) -> Result<u8> {
     }
 }
 
+fn parse_fixed_width(s: &str) -> Result<(String, usize)> {
+    let (name, width) = s
+        .rsplit_once(':')
+        .ok_or_else(|| anyhow!("expected column of the form NAME:WIDTH, got `{}`", s))?;
+
+    match width.parse() {
+        Ok(width) if width > 0 => Ok((name.to_owned(), width)),
+        _ => Err(anyhow!("invalid column width `{}`", width)),
+    }
+}
+
 fn parse_unescaped(s: &str) -> Result<String> {
     unescape(s).ok_or_else(|| anyhow!("string contains invalid escape sequences: `{}`", s))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_encoding_ignores_case() {
+        for (arg, encoding) in [
+            ("YAML", Encoding::Yaml),
+            ("Yaml", Encoding::Yaml),
+            ("yml", Encoding::Yaml),
+            ("json5", Encoding::Json5),
+        ] {
+            let opts = Options::try_parse_from(["dts", "-i", arg, "-o", arg]).unwrap();
+            assert_eq!(opts.input.input_encoding, Some(encoding));
+            assert_eq!(opts.output.output_encoding, Some(encoding));
+        }
+
+        let err = Options::try_parse_from(["dts", "-i", "yamll"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::InvalidValue);
+        assert!(err.to_string().contains("possible values: json, yaml"));
+    }
+
+    #[test]
+    fn test_fixed_width() {
+        let opts = Options::try_parse_from(["dts", "--fixed-width", "name:10,code:4"]).unwrap();
+        assert_eq!(
+            opts.input.fixed_widths,
+            vec![("name".to_owned(), 10), ("code".to_owned(), 4)]
+        );
+
+        assert!(Options::try_parse_from(["dts", "--fixed-width", "name"]).is_err());
+        assert!(Options::try_parse_from(["dts", "--fixed-width", "name:0"]).is_err());
+    }
+}
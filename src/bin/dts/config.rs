@@ -0,0 +1,131 @@
+//! Support for loading default options from a config file.
+
+use crate::args::Options;
+use anyhow::{anyhow, Context, Result};
+use clap::{parser::ValueSource, ArgMatches, CommandFactory, Parser};
+use dts::{de::Deserializer, Source};
+use serde_json::Value;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Parses the command line arguments. If `--config` is provided, the options from the config file
+/// are used as defaults for all options that are not provided on the command line.
+pub fn parse_options() -> Result<Options> {
+    let args: Vec<OsString> = std::env::args_os().collect();
+
+    // The command line is only validated after the config args were merged, so that the config
+    // file can provide options that are required by options on the command line.
+    let matches = match Options::command()
+        .ignore_errors(true)
+        .try_get_matches_from(&args)
+    {
+        Ok(matches) => matches,
+        Err(_) => return Ok(Options::parse_from(args)),
+    };
+
+    let config_args = match matches.get_one::<PathBuf>("config") {
+        Some(path) => load_config_args(path, &matches)?,
+        None => Vec::new(),
+    };
+
+    // Config args are inserted after the binary name so that they are parsed as if they were
+    // provided on the command line.
+    let args = args[..1]
+        .iter()
+        .cloned()
+        .chain(config_args)
+        .chain(args[1..].iter().cloned());
+
+    Ok(Options::parse_from(args))
+}
+
+// Reads the config file at `path` and converts it into command line arguments. Options that are
+// provided on the command line or that conflict with an option provided on the command line are
+// skipped.
+fn load_config_args(path: &Path, matches: &ArgMatches) -> Result<Vec<OsString>> {
+    let source = Source::from(path);
+    let encoding = source
+        .encoding()
+        .context("unable to detect config file encoding from its file extension")?;
+    let reader = source
+        .to_reader()
+        .with_context(|| format!("failed to read config file `{}`", path.display()))?;
+    let config = Deserializer::new(reader)
+        .deserialize(encoding)
+        .with_context(|| format!("failed to deserialize config file `{}`", path.display()))?;
+
+    let config = match config {
+        Value::Object(config) => config,
+        _ => {
+            return Err(anyhow!(
+                "config file `{}` must contain an object",
+                path.display()
+            ))
+        }
+    };
+
+    let cmd = Options::command();
+    let from_cli = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+    let cli_args: Vec<_> = cmd
+        .get_arguments()
+        .filter(|arg| from_cli(arg.get_id().as_str()))
+        .collect();
+    let mut args = Vec::new();
+
+    for (key, value) in config {
+        let long = key.replace('_', "-");
+        let arg = cmd
+            .get_arguments()
+            .find(|arg| arg.get_long() == Some(long.as_str()))
+            .filter(|arg| arg.get_id() != "config")
+            .with_context(|| format!("unknown option `{}` in config file", key))?;
+
+        // Conflicts are only declared on one of the conflicting arguments, so they need to be
+        // checked in both directions.
+        let conflicts_with_cli = cmd
+            .get_arg_conflicts_with(arg)
+            .iter()
+            .any(|other| from_cli(other.get_id().as_str()))
+            || cli_args.iter().any(|other| {
+                cmd.get_arg_conflicts_with(other)
+                    .iter()
+                    .any(|other| other.get_id() == arg.get_id())
+            });
+
+        if from_cli(arg.get_id().as_str()) || conflicts_with_cli {
+            continue;
+        }
+
+        let flag = format!("--{}", arg.get_long().unwrap());
+        let takes_value = arg.get_action().takes_values();
+
+        let values = match value {
+            Value::Array(values) if takes_value => values,
+            value if takes_value => vec![value],
+            Value::Bool(true) => {
+                args.push(flag.into());
+                continue;
+            }
+            Value::Bool(false) => continue,
+            _ => return Err(anyhow!("option `{}` in config file must be a boolean", key)),
+        };
+
+        for value in values {
+            let value = match value {
+                Value::String(s) => s,
+                Value::Number(_) | Value::Bool(_) => value.to_string(),
+                _ => {
+                    return Err(anyhow!(
+                        "option `{}` in config file must be a string, number or boolean",
+                        key
+                    ))
+                }
+            };
+
+            // Use `--flag=value` so that values starting with `-` are not mistaken for flags.
+            args.push(format!("{}={}", flag, value).into());
+        }
+    }
+
+    Ok(args)
+}
@@ -9,7 +9,7 @@ use bat::{assets::HighlightingAssets, config::Config, controller::Controller, In
 use dts::Encoding;
 use std::io::{self, Write};
 use std::path::Path;
-use termcolor::{ColorSpec, StandardStream, WriteColor};
+use termcolor::{Color, ColorSpec, StandardStream, WriteColor};
 
 /// ColoredStdoutWriter writes data to stdout and may or may not colorize it.
 pub struct ColoredStdoutWriter<'a> {
@@ -174,7 +174,7 @@ impl<'a> SyntaxHighlighter<'a> {
 
         match ctrl.run(vec![input], None) {
             Ok(_) => Ok(()),
-            Err(err) => Err(io::Error::new(io::ErrorKind::Other, err.to_string())),
+            Err(err) => Err(io::Error::other(err.to_string())),
         }
     }
 }
@@ -184,6 +184,43 @@ pub fn load_assets() -> HighlightingAssets {
     HighlightingAssets::from_binary()
 }
 
+/// Prints a unified diff to stdout. Removed lines are colored red, added lines green.
+pub fn print_diff(diff: &str, color_choice: ColorChoice) -> io::Result<()> {
+    let mut stdout = StandardStream::stdout(color_choice.into());
+
+    for line in diff.split_inclusive('\n') {
+        let mut spec = ColorSpec::new();
+
+        if line.starts_with("---") || line.starts_with("+++") {
+            spec.set_bold(true);
+        } else if line.starts_with("@@") {
+            spec.set_fg(Some(Color::Cyan));
+        } else if line.starts_with('-') {
+            spec.set_fg(Some(Color::Red));
+        } else if line.starts_with('+') {
+            spec.set_fg(Some(Color::Green));
+        }
+
+        if spec.is_none() {
+            stdout.write_all(line.as_bytes())?;
+            continue;
+        }
+
+        // Reset colors before the line break to avoid coloring the start of the next line.
+        let (content, newline) = match line.strip_suffix('\n') {
+            Some(content) => (content, "\n"),
+            None => (line, ""),
+        };
+
+        stdout.set_color(&spec)?;
+        stdout.write_all(content.as_bytes())?;
+        stdout.reset()?;
+        stdout.write_all(newline.as_bytes())?;
+    }
+
+    Ok(())
+}
+
 /// Prints available themes to stdout.
 pub fn print_themes(color_choice: ColorChoice) -> io::Result<()> {
     let example = include_bytes!("assets/example.json");
@@ -1,4 +1,5 @@
 mod args;
+mod config;
 #[cfg(feature = "color")]
 mod highlighting;
 mod output;
@@ -6,23 +7,31 @@ mod paging;
 mod utils;
 
 #[cfg(feature = "color")]
-use crate::highlighting::{print_themes, ColoredStdoutWriter, HighlightingConfig};
+use crate::highlighting::{print_diff, print_themes, ColoredStdoutWriter, HighlightingConfig};
 use crate::{
     args::{InputOptions, Options, OutputOptions, TransformOptions},
-    output::StdoutWriter,
+    output::{compact_by_default, StdoutWriter},
     paging::PagingConfig,
 };
 use anyhow::{anyhow, Context, Result};
-use clap::{Command, CommandFactory, Parser};
+use clap::{Command, CommandFactory};
 use clap_complete::{generate, Shell};
-use dts::{de::Deserializer, filter::Filter, ser::Serializer, Encoding, Error, Sink, Source};
-use rayon::prelude::*;
-use serde_json::Value;
+use dts::{
+    de::Deserializer,
+    filter::Filter,
+    ser::{GronQuote, SerializeOptions, Serializer},
+    Encoding, Error, Sink, Source, SourceReader,
+};
+use rayon::{prelude::*, ThreadPoolBuilder};
+use serde_json::{json, Value};
+use similar::TextDiff;
+use std::collections::BTreeMap;
 use std::fs::{self, File};
-use std::io::{self, BufWriter, IsTerminal};
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::path::Path;
 
 fn deserialize(source: &Source, opts: &InputOptions) -> Result<Value> {
-    let reader = source
+    let mut reader = source
         .to_reader()
         .with_context(|| format!("failed to create reader for source `{}`", source))?;
 
@@ -31,13 +40,56 @@ fn deserialize(source: &Source, opts: &InputOptions) -> Result<Value> {
         .or_else(|| reader.encoding())
         .context("unable to detect input encoding, please provide it explicitly via -i")?;
 
+    ensure_text_input(&mut reader, source, encoding, opts)?;
+
     let mut de = Deserializer::with_options(reader, opts.into());
 
     de.deserialize(encoding)
         .with_context(|| format!("failed to deserialize `{}` from `{}`", encoding, source))
 }
 
+// Returns an error if the input looks like binary data although `encoding` is a text encoding,
+// unless --allow-binary is set.
+fn ensure_text_input(
+    reader: &mut SourceReader,
+    source: &Source,
+    encoding: Encoding,
+    opts: &InputOptions,
+) -> Result<()> {
+    if !opts.allow_binary && !accepts_binary(encoding) && reader.is_binary()? {
+        return Err(anyhow!(
+            "input from `{}` appears to be binary, pass --allow-binary to read it anyway",
+            source
+        ));
+    }
+
+    Ok(())
+}
+
+// Returns `true` if input in `encoding` may legitimately contain binary data.
+fn accepts_binary(encoding: Encoding) -> bool {
+    match encoding {
+        Encoding::Plist => true,
+        #[cfg(feature = "excel")]
+        Encoding::Xlsx => true,
+        _ => false,
+    }
+}
+
 fn deserialize_many(sources: &[Source], opts: &InputOptions) -> Result<Value> {
+    match opts.jobs {
+        // Use a dedicated thread pool for deserialization only if parallelism was limited
+        // explicitly, otherwise rayon's global thread pool is used.
+        Some(jobs) => ThreadPoolBuilder::new()
+            .num_threads(jobs.get())
+            .build()
+            .context("failed to build thread pool")?
+            .install(|| deserialize_parallel(sources, opts)),
+        None => deserialize_parallel(sources, opts),
+    }
+}
+
+fn deserialize_parallel(sources: &[Source], opts: &InputOptions) -> Result<Value> {
     let results = if opts.continue_on_error {
         sources
             .par_iter()
@@ -56,40 +108,110 @@ fn deserialize_many(sources: &[Source], opts: &InputOptions) -> Result<Value> {
             .collect::<Result<Vec<_>>>()?
     };
 
+    let results = results.into_iter().map(|(src, val)| {
+        let val = if opts.with_source_meta {
+            json!({"source": src.to_string(), "data": val})
+        } else {
+            val
+        };
+
+        (src, val)
+    });
+
     if opts.file_paths {
         Ok(Value::Object(
-            results
-                .into_iter()
-                .map(|res| (res.0.to_string(), res.1))
-                .collect(),
+            results.map(|res| (res.0.to_string(), res.1)).collect(),
         ))
     } else {
-        Ok(Value::Array(results.into_iter().map(|res| res.1).collect()))
+        Ok(Value::Array(results.map(|res| res.1).collect()))
     }
 }
 
-fn transform(value: Value, opts: &TransformOptions) -> Result<Value> {
-    match &opts.jq_expression {
-        Some(expr) => {
+// A compiled jq expression together with its position in the chain of transformations.
+struct TransformStep {
+    index: usize,
+    position: String,
+    expr: String,
+    filter: Filter,
+}
+
+// Reads and compiles all jq expressions in order.
+fn compile_transforms(opts: &TransformOptions) -> Result<Vec<TransformStep>> {
+    let multiple = opts.jq_expressions.len() > 1;
+
+    opts.jq_expressions
+        .iter()
+        .enumerate()
+        .map(|(index, expr)| {
+            // Only mention the position of the expression if there is more than one.
+            let position = if multiple {
+                format!(" #{}", index + 1)
+            } else {
+                String::new()
+            };
+
             let expr = match expr.strip_prefix('@') {
-                Some(path) => fs::read_to_string(path)?,
+                Some(path) => fs::read_to_string(path).with_context(|| {
+                    format!("failed to read jq expression{} from `{}`", position, path)
+                })?,
                 None => expr.to_owned(),
             };
 
-            let filter = Filter::new(&expr)?;
+            let filter =
+                Filter::new(&expr).with_context(|| format!("invalid jq expression{}", position))?;
+
+            Ok(TransformStep {
+                index,
+                position,
+                expr,
+                filter,
+            })
+        })
+        .collect()
+}
 
-            filter.apply(value).context("failed to transform value")
+fn apply_transforms(value: Value, steps: &[TransformStep], trace: bool) -> Result<Value> {
+    steps.iter().try_fold(value, |value, step| {
+        let value = step.filter.apply(value).with_context(|| {
+            format!(
+                "failed to transform value with jq expression{}",
+                step.position
+            )
+        })?;
+
+        if trace {
+            eprintln!("[jq #{} `{}`] {}", step.index + 1, step.expr.trim(), value);
         }
-        None => Ok(value),
-    }
+
+        Ok(value)
+    })
 }
 
-fn serialize(sink: &Sink, value: Value, opts: &OutputOptions) -> Result<()> {
+fn transform(value: Value, opts: &TransformOptions) -> Result<Value> {
+    let steps = compile_transforms(opts)?;
+    apply_transforms(value, &steps, opts.trace_transforms)
+}
+
+// Serializes the value to the sink and returns the encoding that was used.
+fn serialize(
+    sink: &Sink,
+    value: Value,
+    opts: &OutputOptions,
+    input: &InputOptions,
+) -> Result<Encoding> {
     let encoding = opts
         .output_encoding
         .or_else(|| sink.encoding())
         .unwrap_or(Encoding::Json);
 
+    let ser_opts = serialize_options(sink, opts, input);
+
+    if let (Sink::Path(path), true) = (sink, opts.atomic) {
+        return serialize_atomic(path, encoding, value, ser_opts)
+            .map(|()| encoding)
+            .with_context(|| format!("failed to serialize `{}` to `{}`", encoding, sink));
+    }
+
     let paging_config = PagingConfig::new(opts.paging, opts.pager.as_deref());
 
     #[cfg(feature = "color")]
@@ -108,22 +230,219 @@ fn serialize(sink: &Sink, value: Value, opts: &OutputOptions) -> Result<()> {
         #[cfg(not(feature = "color"))]
         Sink::Stdout => Box::new(StdoutWriter::new(paging_config)),
         Sink::Path(path) => Box::new(
-            File::create(path)
+            create_file(path, opts)
                 .with_context(|| format!("failed to create writer for sink `{}`", sink))?,
         ),
     };
 
-    let mut ser = Serializer::with_options(BufWriter::new(writer), opts.into());
+    let mut ser = Serializer::with_options(BufWriter::new(writer), ser_opts);
 
     match ser.serialize(encoding, value) {
-        Ok(()) => Ok(()),
-        Err(Error::Io(err)) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        Ok(()) => Ok(encoding),
+        Err(Error::Io(err)) if err.kind() == io::ErrorKind::BrokenPipe => Ok(encoding),
         Err(err) => Err(err),
     }
     .with_context(|| format!("failed to serialize `{}` to `{}`", encoding, sink))
 }
 
-fn serialize_many(sinks: &[Sink], value: Value, opts: &OutputOptions) -> Result<()> {
+// Creates the output file at `path`, or opens it for appending if --append is set.
+fn create_file(path: &Path, opts: &OutputOptions) -> io::Result<File> {
+    File::options()
+        .write(true)
+        .create(true)
+        .append(opts.append)
+        .truncate(!opts.append)
+        .open(path)
+}
+
+// Reads JSON values from the source one at a time, transforms each of them individually and
+// writes the results as compact JSON lines to the sink. At no point more than one value is held in
+// memory.
+fn process_stream(
+    source: &Source,
+    sink: &Sink,
+    input: &InputOptions,
+    transform: &TransformOptions,
+    output: &OutputOptions,
+) -> Result<()> {
+    let mut reader = source
+        .to_reader()
+        .with_context(|| format!("failed to create reader for source `{}`", source))?;
+
+    for encoding in [
+        input.input_encoding.or_else(|| reader.encoding()),
+        output.output_encoding.or_else(|| sink.encoding()),
+    ] {
+        match encoding {
+            None | Some(Encoding::Json) => {}
+            Some(encoding) => {
+                return Err(anyhow!(
+                    "--stream only supports JSON input and output, got `{}`",
+                    encoding
+                ))
+            }
+        }
+    }
+
+    ensure_text_input(&mut reader, source, Encoding::Json, input)?;
+
+    let steps = compile_transforms(transform)?;
+
+    let writer: Box<dyn io::Write> = match sink {
+        Sink::Stdout => Box::new(io::stdout().lock()),
+        Sink::Path(path) => Box::new(
+            create_file(path, output)
+                .with_context(|| format!("failed to create writer for sink `{}`", sink))?,
+        ),
+    };
+
+    let mut writer = BufWriter::new(writer);
+
+    let mut ser_opts = SerializeOptions::from(output);
+    ser_opts.compact = Some(true);
+    ser_opts.newline = true;
+
+    let mut ser = Serializer::with_options(&mut writer, ser_opts);
+
+    let values = serde_json::Deserializer::from_reader(reader).into_iter::<Value>();
+
+    let result = values.enumerate().try_for_each(|(i, value)| {
+        let value = value.with_context(|| {
+            format!(
+                "failed to deserialize value #{} of `json` stream from `{}`",
+                i + 1,
+                source
+            )
+        })?;
+
+        let value = apply_transforms(value, &steps, transform.trace_transforms)?;
+
+        Ok(ser.serialize(Encoding::Json, value)?)
+    });
+
+    match result.and_then(|()| Ok(writer.flush()?)) {
+        Err(err) if is_broken_pipe(&err) => Ok(()),
+        result => result.with_context(|| format!("failed to stream `json` to `{}`", sink)),
+    }
+}
+
+// Returns `true` if the error was caused by a closed stdout, e.g. when piping output into `head`.
+fn is_broken_pipe(err: &anyhow::Error) -> bool {
+    err.chain()
+        .any(|err| match err.downcast_ref::<io::Error>() {
+            Some(err) => err.kind() == io::ErrorKind::BrokenPipe,
+            None => match err.downcast_ref::<Error>() {
+                Some(Error::Io(err)) => err.kind() == io::ErrorKind::BrokenPipe,
+                _ => false,
+            },
+        })
+}
+
+// Serializes the value into a temporary file next to `path` first and only replaces `path` with it
+// once serialization succeeded and the data was synced to disk. The temporary file is removed on
+// errors.
+fn serialize_atomic(
+    path: &Path,
+    encoding: Encoding,
+    value: Value,
+    opts: SerializeOptions,
+) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("`{}` is not a valid file path", path.display()))?;
+    let tmp_path = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+
+    let write_tmp = || -> Result<()> {
+        let file = File::create(&tmp_path)?;
+
+        // Keep the permissions of the file that is replaced.
+        if let Ok(metadata) = fs::metadata(path) {
+            file.set_permissions(metadata.permissions())?;
+        }
+
+        let mut writer = BufWriter::new(file);
+        Serializer::with_options(&mut writer, opts).serialize(encoding, value)?;
+        writer.into_inner()?.sync_all()?;
+        Ok(fs::rename(&tmp_path, path)?)
+    };
+
+    write_tmp().inspect_err(|_| {
+        let _ = fs::remove_file(&tmp_path);
+    })
+}
+
+fn serialize_options(sink: &Sink, opts: &OutputOptions, input: &InputOptions) -> SerializeOptions {
+    let mut ser_opts = SerializeOptions::from(opts);
+    ser_opts
+        .compact
+        .get_or_insert_with(|| compact_by_default(sink, io::stdout().is_terminal()));
+    // Preserved YAML tags should also be emitted again when serializing.
+    ser_opts.preserve_yaml_tags = input.preserve_yaml_tags;
+    ser_opts
+}
+
+fn validate_sink(sink: &Sink, opts: &OutputOptions) -> Result<()> {
+    if let Sink::Path(path) = sink {
+        if !path.exists() {
+            return Ok(());
+        }
+
+        if !path.is_file() {
+            return Err(anyhow!(
+                "output file `{}` exists but is not a file",
+                path.display()
+            ));
+        } else if !opts.overwrite && !opts.append {
+            return Err(anyhow!(
+                "output file `{}` exists, pass --overwrite to overwrite it",
+                path.display()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// Serializes the value to the sinks, partitions or shards depending on the output options and
+// returns the sinks that were written together with their encoding.
+fn serialize_all(
+    sinks: &[Sink],
+    value: Value,
+    opts: &OutputOptions,
+    input: &InputOptions,
+) -> Result<Vec<(Sink, Encoding)>> {
+    if let (Some(field), Some(dir)) = (&opts.partition_by, &opts.partition_dir) {
+        return serialize_partitioned(field, dir, value, opts, input);
+    }
+
+    if let Some(max_bytes) = opts.shard_max_bytes {
+        return match sinks {
+            [Sink::Path(path)] => serialize_sharded(path, max_bytes, value, opts, input),
+            _ => Err(anyhow!(
+                "sharding output requires exactly one output file to derive shard file names from"
+            )),
+        };
+    }
+
+    if sinks.len() <= 1 {
+        let sink = sinks.first().cloned().unwrap_or(Sink::Stdout);
+        let encoding = serialize(&sink, value, opts, input)?;
+        Ok(vec![(sink, encoding)])
+    } else {
+        serialize_many(sinks, value, opts, input)
+    }
+}
+
+fn serialize_many(
+    sinks: &[Sink],
+    value: Value,
+    opts: &OutputOptions,
+    input: &InputOptions,
+) -> Result<Vec<(Sink, Encoding)>> {
     let values = match value {
         Value::Array(mut values) => {
             if sinks.len() < values.len() {
@@ -152,7 +471,377 @@ fn serialize_many(sinks: &[Sink], value: Value, opts: &OutputOptions) -> Result<
     sinks
         .iter()
         .zip(values)
-        .try_for_each(|(file, value)| serialize(file, value, opts))
+        .map(|(sink, value)| Ok((sink.clone(), serialize(sink, value, opts, input)?)))
+        .collect()
+}
+
+fn serialize_partitioned(
+    field: &str,
+    dir: &Path,
+    value: Value,
+    opts: &OutputOptions,
+    input: &InputOptions,
+) -> Result<Vec<(Sink, Encoding)>> {
+    let values = match value {
+        Value::Array(values) => values,
+        _ => {
+            return Err(anyhow!(
+                "when partitioning output, the data must be an array"
+            ))
+        }
+    };
+
+    let encoding = opts.output_encoding.unwrap_or(Encoding::Json);
+
+    // Partitions are keyed by file stem, but also keep track of the raw field value to detect
+    // distinct values that map to the same file.
+    let mut partitions: BTreeMap<String, (Value, Vec<Value>)> = BTreeMap::new();
+
+    for value in values {
+        let key = value.get(field).cloned().unwrap_or(Value::Null);
+        let file_stem = partition_file_stem(&key);
+
+        let (existing, values) = partitions
+            .entry(file_stem.clone())
+            .or_insert_with(|| (key.clone(), Vec::new()));
+
+        if *existing != key {
+            return Err(anyhow!(
+                "partition values `{}` and `{}` of field `{}` both map to output file `{}`",
+                existing,
+                key,
+                field,
+                dir.join(format!("{}.{}", file_stem, encoding)).display()
+            ));
+        }
+
+        values.push(value);
+    }
+
+    let sinks = partitions
+        .into_iter()
+        .map(|(file_stem, (_, values))| {
+            let path = dir.join(format!("{}.{}", file_stem, encoding));
+            (Sink::Path(path), Value::Array(values))
+        })
+        .collect::<Vec<_>>();
+
+    for (sink, _) in &sinks {
+        validate_sink(sink, opts)?;
+    }
+
+    fs::create_dir_all(dir)
+        .with_context(|| format!("failed to create partition directory `{}`", dir.display()))?;
+
+    sinks
+        .into_iter()
+        .map(|(sink, value)| {
+            let encoding = serialize(&sink, value, opts, input)?;
+            Ok((sink, encoding))
+        })
+        .collect()
+}
+
+fn serialize_sharded(
+    path: &Path,
+    max_bytes: usize,
+    value: Value,
+    opts: &OutputOptions,
+    input: &InputOptions,
+) -> Result<Vec<(Sink, Encoding)>> {
+    let values = match value {
+        Value::Array(values) => values,
+        _ => return Err(anyhow!("when sharding output, the data must be an array")),
+    };
+
+    let sink = Sink::Path(path.to_path_buf());
+    let encoding = opts
+        .output_encoding
+        .or_else(|| sink.encoding())
+        .unwrap_or(Encoding::Json);
+    let ser_opts = serialize_options(&sink, opts, input);
+
+    let serialized_len = |value: Value| -> Result<usize> {
+        let mut buf = Vec::new();
+        let mut ser = Serializer::with_options(&mut buf, ser_opts.clone());
+        ser.serialize(encoding, value)?;
+        Ok(buf.len())
+    };
+
+    let mut shards = Vec::new();
+    let mut shard = Vec::new();
+    let mut shard_len = 0;
+
+    for (index, value) in values.into_iter().enumerate() {
+        // Each element is serialized on its own and as a pair of itself. This yields the size of a
+        // shard that starts with the element and the number of bytes it adds to a shard as a
+        // subsequent element including separators, without re-serializing the whole shard for
+        // every element.
+        let first_len = serialized_len(Value::Array(vec![value.clone()]))?;
+        let next_len =
+            serialized_len(Value::Array(vec![value.clone(), value.clone()]))? - first_len;
+
+        if first_len > max_bytes {
+            return Err(anyhow!(
+                "array element at index {} exceeds the shard size limit of {} bytes",
+                index,
+                max_bytes
+            ));
+        }
+
+        if shard.is_empty() {
+            shard_len = first_len;
+        } else if shard_len + next_len > max_bytes {
+            shards.push(std::mem::take(&mut shard));
+            shard_len = first_len;
+        } else {
+            shard_len += next_len;
+        }
+
+        shard.push(value);
+    }
+
+    if !shard.is_empty() || shards.is_empty() {
+        shards.push(shard);
+    }
+
+    // The sizes above are only estimates for encodings where the size of an element depends on its
+    // position, e.g. the growing array indices of gron. Shards that turn out to be too large are
+    // cut at the largest prefix that still fits and the remaining elements are moved to the front
+    // of the next shard.
+    let mut fitted = Vec::with_capacity(shards.len());
+    let mut shards = shards.into_iter();
+    let mut carry = Vec::new();
+
+    loop {
+        let mut shard = carry;
+
+        match shards.next() {
+            Some(next) => shard.extend(next),
+            None if shard.is_empty() => break,
+            None => {}
+        }
+
+        carry = Vec::new();
+
+        if shard.len() > 1 && serialized_len(Value::Array(shard.clone()))? > max_bytes {
+            let (mut fits, mut exceeds) = (1, shard.len());
+
+            while exceeds - fits > 1 {
+                let mid = fits + (exceeds - fits) / 2;
+
+                if serialized_len(Value::Array(shard[..mid].to_vec()))? > max_bytes {
+                    exceeds = mid;
+                } else {
+                    fits = mid;
+                }
+            }
+
+            carry = shard.split_off(fits);
+        }
+
+        fitted.push(shard);
+    }
+
+    let file_stem = path.file_stem().unwrap_or_default().to_string_lossy();
+
+    let sinks = fitted
+        .into_iter()
+        .enumerate()
+        .map(|(index, values)| {
+            let file_name = match path.extension() {
+                Some(ext) => format!("{}.{}.{}", file_stem, index, ext.to_string_lossy()),
+                None => format!("{}.{}", file_stem, index),
+            };
+
+            (
+                Sink::Path(path.with_file_name(file_name)),
+                Value::Array(values),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    for (sink, _) in &sinks {
+        validate_sink(sink, opts)?;
+    }
+
+    sinks
+        .into_iter()
+        .map(|(sink, value)| {
+            let encoding = serialize(&sink, value, opts, input)?;
+            Ok((sink, encoding))
+        })
+        .collect()
+}
+
+// Writes a JSON array describing all output files that were written to `path`. Output written to
+// stdout is not included.
+fn write_manifest(path: &Path, written: &[(Sink, Encoding)]) -> Result<()> {
+    let entries = written
+        .iter()
+        .filter_map(|(sink, encoding)| match sink {
+            Sink::Path(path) => Some((path, encoding)),
+            Sink::Stdout => None,
+        })
+        .map(|(path, encoding)| {
+            let metadata = fs::metadata(path)
+                .with_context(|| format!("failed to read metadata of `{}`", path.display()))?;
+
+            Ok(json!({
+                "path": path,
+                "bytes": metadata.len(),
+                "encoding": encoding.as_str(),
+            }))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut writer = BufWriter::new(
+        File::create(path)
+            .with_context(|| format!("failed to create manifest `{}`", path.display()))?,
+    );
+    serde_json::to_writer_pretty(&mut writer, &entries)?;
+    writeln!(writer)?;
+    Ok(writer.flush()?)
+}
+
+// Produces a file stem that is safe to use as a file name from a partition field value. Characters
+// other than alphanumerics, `-`, `_` and `.` are replaced with `_`. Records where the field is
+// absent are written to the `null` partition.
+fn partition_file_stem(value: &Value) -> String {
+    let stem: String = match value {
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+    .chars()
+    .map(|c| match c {
+        c if c.is_alphanumeric() => c,
+        '-' | '_' | '.' => c,
+        _ => '_',
+    })
+    .collect();
+
+    if stem.chars().all(|c| c == '.') {
+        // Prevent empty file stems and `.` or `..` which would escape the directory.
+        stem.replace('.', "_") + "_"
+    } else {
+        stem
+    }
+}
+
+fn assert_output(
+    path: &Path,
+    value: Value,
+    opts: &OutputOptions,
+    input: &InputOptions,
+) -> Result<()> {
+    match diff_output(path, value, opts, input)? {
+        Some(diff) => Err(anyhow!(
+            "output does not match `{}`:\n\n{}",
+            path.display(),
+            diff
+        )),
+        None => Ok(()),
+    }
+}
+
+// Prints the diff between the contents of `path` and the output to stdout. Nothing is printed if
+// they are equal.
+fn print_output_diff(
+    path: &Path,
+    value: Value,
+    opts: &OutputOptions,
+    input: &InputOptions,
+) -> Result<()> {
+    let Some(diff) = diff_output(path, value, opts, input)? else {
+        return Ok(());
+    };
+
+    #[cfg(feature = "color")]
+    if opts.color.should_colorize() {
+        return Ok(print_diff(&diff, opts.color)?);
+    }
+
+    Ok(io::stdout().write_all(diff.as_bytes())?)
+}
+
+// Serializes the value and compares it against the contents of `path`. Returns a unified diff if
+// they differ and `None` otherwise.
+//
+// Except for text, serialize-only encodings and gron with non-JSON quoting, both sides are
+// deserialized and compared structurally so that differences in formatting are ignored. The diff
+// is computed on their pretty printed JSON representation.
+fn diff_output(
+    path: &Path,
+    value: Value,
+    opts: &OutputOptions,
+    input: &InputOptions,
+) -> Result<Option<String>> {
+    let encoding = opts
+        .output_encoding
+        .or_else(|| Encoding::from_path(path))
+        .unwrap_or(Encoding::Json);
+
+    let mut ser_opts = SerializeOptions::from(opts);
+    ser_opts.preserve_yaml_tags = input.preserve_yaml_tags;
+
+    let mut buf = Vec::new();
+    let mut ser = Serializer::with_options(&mut buf, ser_opts);
+    ser.serialize(encoding, value)
+        .with_context(|| format!("failed to serialize `{}`", encoding))?;
+
+    let expected = fs::read(path)
+        .with_context(|| format!("failed to read expected output from `{}`", path.display()))?;
+
+    // Serialize-only encodings and gron with non-JSON quoting cannot be deserialized for a
+    // structural comparison.
+    let compare_bytes = match encoding {
+        Encoding::Text | Encoding::Shell => true,
+        Encoding::Gron => opts.gron_quote != GronQuote::Json,
+        _ => false,
+    };
+
+    let (expected, actual) = match encoding {
+        _ if compare_bytes => {
+            if expected == buf {
+                return Ok(None);
+            }
+
+            (
+                String::from_utf8_lossy(&expected).into_owned(),
+                String::from_utf8_lossy(&buf).into_owned(),
+            )
+        }
+        encoding => {
+            let expected = Deserializer::new(expected.as_slice())
+                .deserialize(encoding)
+                .with_context(|| {
+                    format!(
+                        "failed to deserialize `{}` from `{}`",
+                        encoding,
+                        path.display()
+                    )
+                })?;
+            let actual = Deserializer::new(buf.as_slice())
+                .deserialize(encoding)
+                .with_context(|| format!("failed to deserialize `{}` from output", encoding))?;
+
+            if expected == actual {
+                return Ok(None);
+            }
+
+            (
+                serde_json::to_string_pretty(&expected)?,
+                serde_json::to_string_pretty(&actual)?,
+            )
+        }
+    };
+
+    let diff = TextDiff::from_lines(&expected, &actual)
+        .unified_diff()
+        .header(&path.display().to_string(), "<output>")
+        .to_string();
+
+    Ok(Some(diff))
 }
 
 fn print_completions(cmd: &mut Command, shell: Shell) {
@@ -160,7 +849,7 @@ fn print_completions(cmd: &mut Command, shell: Shell) {
 }
 
 fn main() -> Result<()> {
-    let opts = Options::parse();
+    let opts = config::parse_options()?;
 
     if let Some(shell) = opts.generate_completion {
         let mut cmd = Options::command();
@@ -212,23 +901,26 @@ fn main() -> Result<()> {
 
     // Validate sinks to prevent accidentally overwriting existing files.
     for sink in &sinks {
-        if let Sink::Path(path) = sink {
-            if !path.exists() {
-                continue;
-            }
+        validate_sink(sink, &opts.output)?;
+    }
 
-            if !path.is_file() {
-                return Err(anyhow!(
-                    "output file `{}` exists but is not a file",
-                    path.display()
-                ));
-            } else if !opts.output.overwrite {
-                return Err(anyhow!(
-                    "output file `{}` exists, pass --overwrite to overwrite it",
-                    path.display()
-                ));
-            }
-        }
+    if let Some(path) = &opts.output.manifest {
+        validate_sink(&Sink::Path(path.clone()), &opts.output)?;
+    }
+
+    if opts.input.stream {
+        return match (sources.as_slice(), dir_sources) {
+            ([source], false) if sinks.len() <= 1 => process_stream(
+                source,
+                sinks.first().unwrap_or(&Sink::Stdout),
+                &opts.input,
+                &opts.transform,
+                &opts.output,
+            ),
+            _ => Err(anyhow!(
+                "--stream requires exactly one input source and at most one output sink"
+            )),
+        };
     }
 
     let value = match (sources.len(), dir_sources) {
@@ -237,11 +929,24 @@ fn main() -> Result<()> {
         (_, _) => deserialize_many(&sources, &opts.input)?,
     };
 
-    let value = transform(value, &opts.transform)?;
+    let mut value = transform(value, &opts.transform)?;
 
-    if sinks.len() <= 1 {
-        serialize(sinks.first().unwrap_or(&Sink::Stdout), value, &opts.output)
-    } else {
-        serialize_many(&sinks, value, &opts.output)
+    if opts.output.emit_schema {
+        value = dts::schema::infer(&value);
+    }
+
+    if let Some(path) = &opts.output.assert {
+        return assert_output(path, value, &opts.output, &opts.input);
+    }
+
+    if let Some(path) = &opts.output.diff_with {
+        return print_output_diff(path, value, &opts.output, &opts.input);
+    }
+
+    let written = serialize_all(&sinks, value, &opts.output, &opts.input)?;
+
+    match &opts.output.manifest {
+        Some(path) => write_manifest(path, &written),
+        None => Ok(()),
     }
 }
@@ -6,6 +6,7 @@ use crate::{
     utils::resolve_cmd,
 };
 use clap::ValueEnum;
+use dts::Sink;
 use std::io::{self, IsTerminal, Stdout};
 use std::process::{Child, Command, Stdio};
 
@@ -72,6 +73,16 @@ impl ColorChoice {
     }
 }
 
+/// Decides whether output for a sink should be compact if neither compact nor pretty output was
+/// requested explicitly. Output to a terminal and to files is pretty printed, while output that is
+/// piped into another program is compact.
+pub fn compact_by_default(sink: &Sink, stdout_is_terminal: bool) -> bool {
+    match sink {
+        Sink::Stdout => !stdout_is_terminal,
+        Sink::Path(_) => false,
+    }
+}
+
 /// StdoutWriter either writes data directly to stdout or passes it through a pager first.
 #[derive(Debug)]
 pub enum StdoutWriter {
@@ -147,3 +158,16 @@ impl Drop for StdoutWriter {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_compact_by_default() {
+        assert!(!compact_by_default(&Sink::Stdout, true));
+        assert!(compact_by_default(&Sink::Stdout, false));
+        assert!(!compact_by_default(&Sink::from("out.json"), true));
+        assert!(!compact_by_default(&Sink::from("out.json"), false));
+    }
+}
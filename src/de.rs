@@ -1,7 +1,14 @@
 //! This module provides a `Deserializer` which supports deserializing input data with various
 //! encodings into a `Value`.
 
-use crate::{key::expand_keys, parsers::gron, Encoding, Result};
+#[cfg(feature = "excel")]
+use crate::value::ValueExt;
+use crate::{
+    key::expand_keys,
+    parsers::{gron, jsonc},
+    yaml, Encoding, Error, Result,
+};
+use clap::ValueEnum;
 use hcl::eval::Evaluate;
 use regex::Regex;
 use serde::Deserialize;
@@ -20,10 +27,30 @@ pub struct DeserializeOptions {
     pub csv_headers_as_keys: bool,
     /// Optional custom delimiter for CSV input.
     pub csv_delimiter: Option<u8>,
+    /// Controls the trimming of leading and trailing whitespace of CSV input fields.
+    pub csv_trim: CsvTrim,
+    /// Optional column names to use as keys for each row's columns. If set, the deserialized row
+    /// data will be of type object and the header line (if any) is not used as keys.
+    pub csv_columns: Option<Vec<String>>,
+    /// Accept CSV rows with varying numbers of fields. If rows are deserialized into objects,
+    /// missing fields of shorter rows are set to empty strings and excess fields of longer rows
+    /// are dropped. Rows deserialized into arrays keep their original length.
+    pub csv_flexible: bool,
     /// Optional regex pattern to split text input at.
     pub text_split_pattern: Option<Regex>,
+    /// Optional column names and widths (in characters) to parse fixed-width text input. If set,
+    /// each non-empty line of text input is sliced at the given widths into an object with
+    /// trimmed fields. Missing fields of short lines are empty strings.
+    pub fixed_widths: Option<Vec<(String, usize)>>,
     /// Simplify input if the encoding supports it.
     pub simplify: bool,
+    /// Optional name of the sheet to read from an Excel workbook. If `None`, the first sheet is
+    /// read. The CSV header options also apply to Excel input.
+    #[cfg(feature = "excel")]
+    pub xlsx_sheet: Option<String>,
+    /// Preserve YAML tags by deserializing tagged values into objects of the form
+    /// `{"__tag__": "!Tag", "value": <value>}`. If `false`, tags are dropped.
+    pub preserve_yaml_tags: bool,
 }
 
 impl DeserializeOptions {
@@ -33,6 +60,31 @@ impl DeserializeOptions {
     }
 }
 
+/// Controls which parts of CSV input get leading and trailing whitespace trimmed.
+#[derive(ValueEnum, Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum CsvTrim {
+    /// Do not trim any whitespace.
+    None,
+    /// Only trim whitespace of header fields.
+    Headers,
+    /// Only trim whitespace of non-header fields.
+    Fields,
+    /// Trim whitespace of header and non-header fields.
+    #[default]
+    All,
+}
+
+impl From<CsvTrim> for csv::Trim {
+    fn from(trim: CsvTrim) -> Self {
+        match trim {
+            CsvTrim::None => csv::Trim::None,
+            CsvTrim::Headers => csv::Trim::Headers,
+            CsvTrim::Fields => csv::Trim::Fields,
+            CsvTrim::All => csv::Trim::All,
+        }
+    }
+}
+
 /// A `DeserializerBuilder` can be used to build a `Deserializer` with certain
 /// `DeserializeOptions`.
 ///
@@ -79,18 +131,77 @@ impl DeserializerBuilder {
         self
     }
 
+    /// Controls the trimming of leading and trailing whitespace of CSV input fields.
+    pub fn csv_trim(&mut self, trim: CsvTrim) -> &mut Self {
+        self.opts.csv_trim = trim;
+        self
+    }
+
+    /// Sets the column names to use as keys for each row's columns of CSV input.
+    pub fn csv_columns<I, S>(&mut self, columns: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.opts.csv_columns = Some(
+            columns
+                .into_iter()
+                .map(|column| column.as_ref().to_owned())
+                .collect(),
+        );
+        self
+    }
+
+    /// Accept CSV rows with varying numbers of fields.
+    pub fn csv_flexible(&mut self, yes: bool) -> &mut Self {
+        self.opts.csv_flexible = yes;
+        self
+    }
+
     /// Sets regex pattern to split text at.
     pub fn text_split_pattern(&mut self, pattern: Regex) -> &mut Self {
         self.opts.text_split_pattern = Some(pattern);
         self
     }
 
+    /// Sets the column names and widths to parse fixed-width text input.
+    pub fn fixed_widths<I, S>(&mut self, widths: I) -> &mut Self
+    where
+        I: IntoIterator<Item = (S, usize)>,
+        S: AsRef<str>,
+    {
+        self.opts.fixed_widths = Some(
+            widths
+                .into_iter()
+                .map(|(name, width)| (name.as_ref().to_owned(), width))
+                .collect(),
+        );
+        self
+    }
+
     /// Simplify input if the encoding supports it.
     pub fn simplifiy(&mut self, yes: bool) -> &mut Self {
         self.opts.simplify = yes;
         self
     }
 
+    /// Preserve YAML tags by deserializing tagged values into objects of the form
+    /// `{"__tag__": "!Tag", "value": <value>}`.
+    pub fn preserve_yaml_tags(&mut self, yes: bool) -> &mut Self {
+        self.opts.preserve_yaml_tags = yes;
+        self
+    }
+
+    /// Sets the name of the sheet to read from an Excel workbook.
+    #[cfg(feature = "excel")]
+    pub fn xlsx_sheet<S>(&mut self, name: S) -> &mut Self
+    where
+        S: AsRef<str>,
+    {
+        self.opts.xlsx_sheet = Some(name.as_ref().to_owned());
+        self
+    }
+
     /// Builds the `Deserializer` for the given reader.
     pub fn build<R>(&self, reader: R) -> Deserializer<R>
     where
@@ -145,19 +256,26 @@ where
             Encoding::Json => self.deserialize_json(),
             Encoding::Toml => self.deserialize_toml(),
             Encoding::Json5 => self.deserialize_json5(),
+            Encoding::Jsonc => self.deserialize_jsonc(),
             Encoding::Csv => self.deserialize_csv(),
             Encoding::QueryString => self.deserialize_query_string(),
             Encoding::Xml => self.deserialize_xml(),
             Encoding::Text => self.deserialize_text(),
             Encoding::Gron => self.deserialize_gron(),
             Encoding::Hcl => self.deserialize_hcl(),
+            Encoding::Plist => self.deserialize_plist(),
+            #[cfg(feature = "excel")]
+            Encoding::Xlsx => self.deserialize_xlsx(),
+            encoding => Err(Error::UnsupportedEncoding(encoding)),
         }
     }
 
     fn deserialize_yaml(&mut self) -> Result<Value> {
+        let preserve_tags = self.opts.preserve_yaml_tags;
+
         let mut values = serde_yaml::Deserializer::from_reader(&mut self.reader)
-            .map(Value::deserialize)
-            .collect::<Result<Vec<_>, _>>()?;
+            .map(|de| yaml::to_json(serde_yaml::Value::deserialize(de)?, preserve_tags))
+            .collect::<Result<Vec<_>>>()?;
 
         // If this was not multi-document YAML, just take the first document's value without
         // wrapping it into an array.
@@ -169,7 +287,18 @@ where
     }
 
     fn deserialize_json(&mut self) -> Result<Value> {
-        Ok(serde_json::from_reader(&mut self.reader)?)
+        let mut de = serde_json::Deserializer::from_reader(&mut self.reader);
+        let first = Value::deserialize(&mut de)?;
+        let mut rest = de.into_iter().collect::<Result<Vec<Value>, _>>()?;
+
+        // Like multi-document YAML, a stream of concatenated JSON values is deserialized into an
+        // array. A single value is returned as-is.
+        if rest.is_empty() {
+            Ok(first)
+        } else {
+            rest.insert(0, first);
+            Ok(Value::Array(rest))
+        }
     }
 
     fn deserialize_toml(&mut self) -> Result<Value> {
@@ -184,34 +313,54 @@ where
         Ok(json5::from_str(&s)?)
     }
 
+    fn deserialize_jsonc(&mut self) -> Result<Value> {
+        let mut s = String::new();
+        self.reader.read_to_string(&mut s)?;
+        Ok(serde_json::from_str(&jsonc::strip(&s))?)
+    }
+
     fn deserialize_csv(&mut self) -> Result<Value> {
-        let keep_first_line = self.opts.csv_without_headers || self.opts.csv_headers_as_keys;
+        let has_headers = self.opts.csv_headers_as_keys || !self.opts.csv_without_headers;
 
         let mut csv_reader = csv::ReaderBuilder::new()
-            .trim(csv::Trim::All)
-            .has_headers(!keep_first_line)
+            .trim(self.opts.csv_trim.into())
+            .has_headers(has_headers)
             .delimiter(self.opts.csv_delimiter.unwrap_or(b','))
+            .flexible(self.opts.csv_flexible)
             .from_reader(&mut self.reader);
 
-        let mut iter = csv_reader.deserialize();
-
-        let value = if self.opts.csv_headers_as_keys {
-            match iter.next() {
-                Some(headers) => {
-                    let headers: Vec<String> = headers?;
+        let flexible = self.opts.csv_flexible;
 
-                    Value::Array(
-                        iter.map(|record| {
-                            Ok(headers.iter().cloned().zip(record?.into_iter()).collect())
-                        })
-                        .collect::<Result<_>>()?,
-                    )
-                }
-                None => Value::Array(Vec::new()),
+        // Pads short rows with empty strings if flexible, excess fields are always dropped.
+        let to_object = |keys: &[String], mut record: Vec<String>| -> Value {
+            if flexible && record.len() < keys.len() {
+                record.resize(keys.len(), String::new());
             }
+
+            keys.iter().cloned().zip(record).collect()
+        };
+
+        let value = if let Some(columns) = &self.opts.csv_columns {
+            Value::Array(
+                csv_reader
+                    .deserialize::<Vec<String>>()
+                    .map(|record| Ok(to_object(columns, record?)))
+                    .collect::<Result<_>>()?,
+            )
+        } else if self.opts.csv_headers_as_keys {
+            let headers: Vec<String> = csv_reader.headers()?.iter().map(String::from).collect();
+
+            Value::Array(
+                csv_reader
+                    .deserialize::<Vec<String>>()
+                    .map(|record| Ok(to_object(&headers, record?)))
+                    .collect::<Result<_>>()?,
+            )
         } else {
             Value::Array(
-                iter.map(|v| Ok(serde_json::to_value(v?)?))
+                csv_reader
+                    .deserialize::<Vec<String>>()
+                    .map(|v| Ok(serde_json::to_value(v?)?))
                     .collect::<Result<_>>()?,
             )
         };
@@ -229,6 +378,16 @@ where
         Ok(serde_xml_rs::from_reader(&mut self.reader)?)
     }
 
+    fn deserialize_plist(&mut self) -> Result<Value> {
+        // Detecting binary plists requires `io::Seek`, so the input needs to be buffered.
+        let mut buf = Vec::new();
+        self.reader.read_to_end(&mut buf)?;
+
+        let value = plist::Value::from_reader(std::io::Cursor::new(buf))?;
+
+        Ok(plist_to_value(value))
+    }
+
     fn deserialize_text(&mut self) -> Result<Value> {
         let mut s = String::new();
         self.reader.read_to_string(&mut s)?;
@@ -238,6 +397,14 @@ where
             None => Regex::new("\n").unwrap(),
         };
 
+        if let Some(widths) = &self.opts.fixed_widths {
+            return Ok(pattern
+                .split(&s)
+                .filter(|line| !line.is_empty())
+                .map(|line| parse_fixed_width_line(line, widths))
+                .collect());
+        }
+
         Ok(Value::Array(
             pattern
                 .split(&s)
@@ -275,6 +442,120 @@ where
 
         Ok(value)
     }
+
+    #[cfg(feature = "excel")]
+    fn deserialize_xlsx(&mut self) -> Result<Value> {
+        use calamine::Reader;
+
+        // Reading xlsx requires `io::Seek`, so the whole workbook needs to be buffered.
+        let mut buf = Vec::new();
+        self.reader.read_to_end(&mut buf)?;
+
+        let mut workbook = calamine::Xlsx::new(std::io::Cursor::new(buf))?;
+
+        let range = match &self.opts.xlsx_sheet {
+            Some(name) => workbook.worksheet_range(name)?,
+            None => workbook
+                .worksheet_range_at(0)
+                .ok_or_else(|| crate::Error::new("workbook does not contain any sheets"))??,
+        };
+
+        let mut rows = range
+            .rows()
+            .map(|row| row.iter().map(xlsx_cell_to_value).collect::<Vec<_>>());
+
+        let value = if self.opts.csv_headers_as_keys {
+            match rows.next() {
+                Some(headers) => {
+                    let headers: Vec<String> =
+                        headers.into_iter().map(Value::into_string).collect();
+
+                    Value::Array(
+                        rows.map(|row| Value::Object(headers.iter().cloned().zip(row).collect()))
+                            .collect(),
+                    )
+                }
+                None => Value::Array(Vec::new()),
+            }
+        } else {
+            if !self.opts.csv_without_headers {
+                rows.next();
+            }
+
+            Value::Array(rows.map(Value::Array).collect())
+        };
+
+        Ok(value)
+    }
+}
+
+// Plist dates are converted into RFC 3339 strings and data into base64 encoded strings.
+fn plist_to_value(value: plist::Value) -> Value {
+    use base64::prelude::*;
+
+    match value {
+        plist::Value::Array(array) => Value::Array(array.into_iter().map(plist_to_value).collect()),
+        plist::Value::Dictionary(dict) => Value::Object(
+            dict.into_iter()
+                .map(|(key, value)| (key, plist_to_value(value)))
+                .collect(),
+        ),
+        plist::Value::Boolean(b) => Value::Bool(b),
+        plist::Value::Data(data) => Value::String(BASE64_STANDARD.encode(data)),
+        plist::Value::Date(date) => Value::String(date.to_xml_format()),
+        plist::Value::Real(f) => Value::from(f),
+        plist::Value::Integer(int) => match int.as_signed() {
+            Some(i) => Value::from(i),
+            None => int.as_unsigned().map(Value::from).unwrap_or(Value::Null),
+        },
+        plist::Value::String(s) => Value::String(s),
+        plist::Value::Uid(uid) => Value::from(uid.get()),
+        // `plist::Value` is non-exhaustive.
+        _ => Value::Null,
+    }
+}
+
+// Excel stores all numbers as floats, so whole numbers are converted into integers. Dates are
+// converted into ISO 8601 strings.
+#[cfg(feature = "excel")]
+fn xlsx_cell_to_value(cell: &calamine::Data) -> Value {
+    use calamine::Data;
+
+    let float_to_value = |f: f64| {
+        if f.fract() == 0.0 && f >= i64::MIN as f64 && f <= i64::MAX as f64 {
+            Value::from(f as i64)
+        } else {
+            Value::from(f)
+        }
+    };
+
+    match cell {
+        Data::Int(i) => Value::from(*i),
+        Data::Float(f) => float_to_value(*f),
+        Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => Value::from(s.as_str()),
+        Data::Bool(b) => Value::Bool(*b),
+        Data::DateTime(dt) if dt.is_datetime() => dt
+            .as_datetime()
+            // The `Debug` representation of `NaiveDateTime` is ISO 8601.
+            .map(|dt| Value::from(format!("{dt:?}")))
+            .unwrap_or_else(|| float_to_value(dt.as_f64())),
+        Data::DateTime(dt) => float_to_value(dt.as_f64()),
+        Data::Error(err) => Value::from(err.to_string()),
+        Data::Empty => Value::Null,
+    }
+}
+
+// Slices `line` into an object at the given column widths and trims each field.
+fn parse_fixed_width_line(line: &str, widths: &[(String, usize)]) -> Value {
+    let mut chars = line.chars();
+
+    widths
+        .iter()
+        .map(|(name, width)| {
+            let field: String = chars.by_ref().take(*width).collect();
+            (name.clone(), Value::String(field.trim().to_owned()))
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -310,6 +591,115 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_deserialize_json() {
+        assert_deserializes_to(Encoding::Json, r#"{"foo": "bar"}"#, json!({"foo": "bar"}));
+        assert_deserializes_to(
+            Encoding::Json,
+            r#"{"a": 1}{"b": 2}
+            {"c": 3}"#,
+            json!([{"a": 1}, {"b": 2}, {"c": 3}]),
+        );
+        assert_deserializes_to(Encoding::Json, "1 [2] \"3\"", json!([1, [2], "3"]));
+
+        let mut de = Deserializer::new(&b""[..]);
+        assert!(de.deserialize(Encoding::Json).is_err());
+        let mut de = Deserializer::new(&b"{} {"[..]);
+        assert!(de.deserialize(Encoding::Json).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_jsonc() {
+        let input = r#"{
+            // Line comment.
+            "url": "https://example.com//path", /* Block comment. */
+            "list": [1, 2, /* 3, */],
+            /*
+             * Multi-line block comment.
+             */
+            "nested": {"a": "/* not a comment */",},
+        }"#;
+
+        assert_deserializes_to(
+            Encoding::Jsonc,
+            input,
+            json!({
+                "url": "https://example.com//path",
+                "list": [1, 2],
+                "nested": {"a": "/* not a comment */"}
+            }),
+        );
+
+        let mut de = Deserializer::new(&b"{\"a\": 1,, }"[..]);
+        assert!(de.deserialize(Encoding::Jsonc).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_yaml_tags() {
+        let input = "bucket: !Ref MyBucket\nname: !Sub '${AWS::StackName}-data'";
+
+        assert_deserializes_to(
+            Encoding::Yaml,
+            input,
+            json!({"bucket": "MyBucket", "name": "${AWS::StackName}-data"}),
+        );
+        assert_builder_deserializes_to(
+            DeserializerBuilder::new().preserve_yaml_tags(true),
+            Encoding::Yaml,
+            input,
+            json!({
+                "bucket": {"__tag__": "!Ref", "value": "MyBucket"},
+                "name": {"__tag__": "!Sub", "value": "${AWS::StackName}-data"}
+            }),
+        );
+    }
+
+    #[test]
+    fn test_deserialize_yaml_aliases() {
+        assert_deserializes_to(
+            Encoding::Yaml,
+            "base: &base\n  foo: bar\nderived:\n  <<: *base\ncopy: *base",
+            json!({
+                "base": {"foo": "bar"},
+                "derived": {"<<": {"foo": "bar"}},
+                "copy": {"foo": "bar"}
+            }),
+        );
+
+        // Recursive aliases and exponential alias expansion ("billion laughs") are rejected by
+        // serde_yaml instead of looping or exhausting memory.
+        let mut de = DeserializerBuilder::new().build("a: &a\n  b: *a\n".as_bytes());
+        let err = de.deserialize(Encoding::Yaml).unwrap_err();
+        assert!(err.to_string().contains("recursion limit exceeded"));
+
+        let mut laughs = String::from(
+            "a: &a [\"lol\",\"lol\",\"lol\",\"lol\",\"lol\",\"lol\",\"lol\",\"lol\",\"lol\"]\n",
+        );
+        for (prev, name) in "abcdefgh".chars().zip("bcdefghi".chars()) {
+            let aliases = vec![format!("*{}", prev); 9].join(",");
+            laughs.push_str(&format!("{name}: &{name} [{aliases}]\n"));
+        }
+
+        let mut de = DeserializerBuilder::new().build(laughs.as_bytes());
+        let err = de.deserialize(Encoding::Yaml).unwrap_err();
+        assert!(err.to_string().contains("repetition limit exceeded"));
+    }
+
+    #[test]
+    fn test_deserialize_json5() {
+        assert_deserializes_to(
+            Encoding::Json5,
+            r#"{
+                // A line comment.
+                foo: 'bar',
+                /* A block comment. */
+                "baz": [1, 2, 3,],
+                qux: 0x10,
+            }"#,
+            json!({"foo": "bar", "baz": [1, 2, 3], "qux": 16}),
+        );
+    }
+
     #[test]
     fn test_deserialize_csv() {
         assert_deserializes_to(
@@ -318,25 +708,108 @@ mod test {
             json!([["col1", "col2"]]),
         );
         assert_builder_deserializes_to(
-            &mut DeserializerBuilder::new().csv_without_headers(true),
+            DeserializerBuilder::new().csv_without_headers(true),
             Encoding::Csv,
             "row1col1,row1col2\nrow2col1,row2col2",
             json!([["row1col1", "row1col2"], ["row2col1", "row2col2"]]),
         );
         assert_builder_deserializes_to(
-            &mut DeserializerBuilder::new().csv_headers_as_keys(true),
+            DeserializerBuilder::new().csv_headers_as_keys(true),
             Encoding::Csv,
             "header1,header2\nrow1col1,row1col2\nrow2col1,row2col2",
             json!([{"header1":"row1col1", "header2":"row1col2"}, {"header1":"row2col1", "header2":"row2col2"}]),
         );
         assert_builder_deserializes_to(
-            &mut DeserializerBuilder::new().csv_delimiter(b'|'),
+            DeserializerBuilder::new().csv_delimiter(b'|'),
             Encoding::Csv,
             "header1|header2\ncol1|col2",
             json!([["col1", "col2"]]),
         );
     }
 
+    #[test]
+    fn test_deserialize_csv_columns() {
+        assert_builder_deserializes_to(
+            DeserializerBuilder::new()
+                .csv_without_headers(true)
+                .csv_columns(["name", "age"]),
+            Encoding::Csv,
+            "foo,1\nbar,2",
+            json!([{"name": "foo", "age": "1"}, {"name": "bar", "age": "2"}]),
+        );
+        assert_builder_deserializes_to(
+            DeserializerBuilder::new()
+                .csv_headers_as_keys(true)
+                .csv_columns(["name", "age"]),
+            Encoding::Csv,
+            "header1,header2\nfoo,1",
+            json!([{"name": "foo", "age": "1"}]),
+        );
+        // Excess fields are ignored.
+        assert_builder_deserializes_to(
+            DeserializerBuilder::new()
+                .csv_without_headers(true)
+                .csv_columns(["name"]),
+            Encoding::Csv,
+            "foo,1\nbar,2",
+            json!([{"name": "foo"}, {"name": "bar"}]),
+        );
+    }
+
+    #[test]
+    fn test_deserialize_csv_flexible() {
+        let input = "a,b,c\n1,2\n1,2,3,4\n";
+
+        let mut de = DeserializerBuilder::new()
+            .csv_headers_as_keys(true)
+            .build(input.as_bytes());
+        assert!(de.deserialize(Encoding::Csv).is_err());
+
+        assert_builder_deserializes_to(
+            DeserializerBuilder::new()
+                .csv_headers_as_keys(true)
+                .csv_flexible(true),
+            Encoding::Csv,
+            input,
+            json!([{"a": "1", "b": "2", "c": ""}, {"a": "1", "b": "2", "c": "3"}]),
+        );
+        assert_builder_deserializes_to(
+            DeserializerBuilder::new().csv_flexible(true),
+            Encoding::Csv,
+            input,
+            json!([["1", "2"], ["1", "2", "3", "4"]]),
+        );
+    }
+
+    #[test]
+    fn test_deserialize_csv_trim() {
+        let input = " header1 , header2 \n col1 , col2 ";
+
+        assert_deserializes_to(Encoding::Csv, input, json!([["col1", "col2"]]));
+        assert_builder_deserializes_to(
+            DeserializerBuilder::new().csv_trim(CsvTrim::None),
+            Encoding::Csv,
+            input,
+            json!([[" col1 ", " col2 "]]),
+        );
+        assert_builder_deserializes_to(
+            DeserializerBuilder::new()
+                .csv_headers_as_keys(true)
+                .csv_trim(CsvTrim::Headers),
+            Encoding::Csv,
+            input,
+            json!([{"header1": " col1 ", "header2": " col2 "}]),
+        );
+        assert_builder_deserializes_to(
+            DeserializerBuilder::new()
+                .csv_headers_as_keys(true)
+                .csv_trim(CsvTrim::Fields),
+            Encoding::Csv,
+            input,
+            json!([{" header1 ": "col1", " header2 ": "col2"}]),
+        );
+    }
+
     #[test]
     fn test_deserialize_text() {
         assert_deserializes_to(
@@ -346,4 +819,80 @@ mod test {
         );
         assert_deserializes_to(Encoding::Text, "", json!([""]));
     }
+
+    #[test]
+    fn test_deserialize_text_fixed_widths() {
+        assert_builder_deserializes_to(
+            DeserializerBuilder::new().fixed_widths([("name", 8), ("code", 4)]),
+            Encoding::Text,
+            "alice   0042\nbob     7\ncarol\n",
+            json!([
+                {"name": "alice", "code": "0042"},
+                {"name": "bob", "code": "7"},
+                {"name": "carol", "code": ""}
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_deserialize_plist() {
+        let expected = json!({
+            "name": "dts",
+            "version": 3,
+            "ratio": 1.5,
+            "enabled": true,
+            "created": "2024-01-02T03:04:05Z",
+            "icon": "AAECZHRz",
+            "tags": ["a", "b"],
+            "nested": {"key": "value"}
+        });
+
+        assert_deserializes_to(
+            Encoding::Plist,
+            include_str!("../tests/fixtures/example.plist"),
+            expected.clone(),
+        );
+
+        let binary: &[u8] = include_bytes!("../tests/fixtures/example.binary.plist");
+        let mut de = DeserializerBuilder::new().build(binary);
+        assert_eq!(de.deserialize(Encoding::Plist).unwrap(), expected);
+    }
+
+    #[cfg(feature = "excel")]
+    #[test]
+    fn test_deserialize_xlsx() {
+        let input: &[u8] = include_bytes!("../tests/fixtures/example.xlsx");
+
+        let deserialize = |builder: &mut DeserializerBuilder| {
+            builder.build(input).deserialize(Encoding::Xlsx).unwrap()
+        };
+
+        assert_eq!(
+            deserialize(&mut DeserializerBuilder::new()),
+            json!([
+                ["alice", 30, 1.5, true, "2024-01-01T00:00:00"],
+                ["bob", null, 2, false, "2024-02-01T00:00:00"]
+            ])
+        );
+        assert_eq!(
+            deserialize(DeserializerBuilder::new().csv_headers_as_keys(true)),
+            json!([
+                {"name": "alice", "age": 30, "score": 1.5, "active": true, "joined": "2024-01-01T00:00:00"},
+                {"name": "bob", "age": null, "score": 2, "active": false, "joined": "2024-02-01T00:00:00"}
+            ])
+        );
+        assert_eq!(
+            deserialize(
+                DeserializerBuilder::new()
+                    .csv_without_headers(true)
+                    .xlsx_sheet("other")
+            ),
+            json!([["foo", 1]])
+        );
+        assert!(DeserializerBuilder::new()
+            .xlsx_sheet("nonexistent")
+            .build(input)
+            .deserialize(Encoding::Xlsx)
+            .is_err());
+    }
 }
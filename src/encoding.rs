@@ -1,10 +1,12 @@
 //! Supported encodings for serialization and deserialization.
 
+use crate::Error;
 use clap::ValueEnum;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::fmt;
 use std::path::Path;
+use std::str::FromStr;
 
 /// Encodings supported by this crate.
 ///
@@ -23,6 +25,8 @@ pub enum Encoding {
     Toml,
     /// ES5 JSON
     Json5,
+    /// JSON with comments
+    Jsonc,
     /// Comma separated values
     Csv,
     /// URL query string
@@ -37,6 +41,14 @@ pub enum Encoding {
     Gron,
     /// HCL
     Hcl,
+    /// Apple property list
+    Plist,
+    /// Shell variable assignments
+    #[clap(alias = "sh")]
+    Shell,
+    /// Excel spreadsheet
+    #[cfg(feature = "excel")]
+    Xlsx,
 }
 
 // Patterns to detect a source encoding by looking at the first line of input. The patterns are
@@ -115,10 +127,58 @@ impl Encoding {
             "yaml" | "yml" => Some(Encoding::Yaml),
             "toml" => Some(Encoding::Toml),
             "json5" => Some(Encoding::Json5),
+            "jsonc" => Some(Encoding::Jsonc),
             "csv" => Some(Encoding::Csv),
             "xml" => Some(Encoding::Xml),
             "txt" | "text" => Some(Encoding::Text),
             "hcl" | "tf" => Some(Encoding::Hcl),
+            "plist" => Some(Encoding::Plist),
+            "sh" => Some(Encoding::Shell),
+            #[cfg(feature = "excel")]
+            "xlsx" => Some(Encoding::Xlsx),
+            _ => None,
+        }
+    }
+
+    /// Creates an `Encoding` from a MIME type as found in a `Content-Type` header. Parameters like
+    /// `charset` are ignored.
+    ///
+    /// Returns `None` if the MIME type does not correspond to any of the supported encodings.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use dts::Encoding;
+    ///
+    /// assert_eq!(
+    ///     Encoding::from_mime("application/json; charset=utf-8"),
+    ///     Some(Encoding::Json)
+    /// );
+    /// assert_eq!(Encoding::from_mime("image/png"), None);
+    /// ```
+    pub fn from_mime(mime: &str) -> Option<Encoding> {
+        let mime = mime.split(';').next()?.trim().to_ascii_lowercase();
+
+        match mime.as_str() {
+            "application/json" | "text/json" => Some(Encoding::Json),
+            "application/yaml" | "application/x-yaml" | "text/yaml" | "text/x-yaml" => {
+                Some(Encoding::Yaml)
+            }
+            "application/toml" | "application/x-toml" | "text/x-toml" => Some(Encoding::Toml),
+            "application/json5" => Some(Encoding::Json5),
+            "text/csv" => Some(Encoding::Csv),
+            "application/x-www-form-urlencoded" => Some(Encoding::QueryString),
+            "application/xml" | "text/xml" => Some(Encoding::Xml),
+            "text/plain" => Some(Encoding::Text),
+            "application/x-plist" => Some(Encoding::Plist),
+            #[cfg(feature = "excel")]
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => {
+                Some(Encoding::Xlsx)
+            }
+            // Structured syntax suffixes as defined in RFC 6839, e.g. `application/ld+json`.
+            mime if mime.ends_with("+json") => Some(Encoding::Json),
+            mime if mime.ends_with("+yaml") => Some(Encoding::Yaml),
+            mime if mime.ends_with("+xml") => Some(Encoding::Xml),
             _ => None,
         }
     }
@@ -148,12 +208,17 @@ impl Encoding {
             Encoding::Yaml => "yaml",
             Encoding::Toml => "toml",
             Encoding::Json5 => "json5",
+            Encoding::Jsonc => "jsonc",
             Encoding::Csv => "csv",
             Encoding::QueryString => "query-string",
             Encoding::Xml => "xml",
             Encoding::Text => "text",
             Encoding::Gron => "gron",
             Encoding::Hcl => "hcl",
+            Encoding::Plist => "plist",
+            Encoding::Shell => "shell",
+            #[cfg(feature = "excel")]
+            Encoding::Xlsx => "xlsx",
         }
     }
 }
@@ -164,6 +229,27 @@ impl fmt::Display for Encoding {
     }
 }
 
+impl FromStr for Encoding {
+    type Err = Error;
+
+    /// Parses an `Encoding` from its name or one of its aliases (e.g. `yml`). Parsing ignores
+    /// case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        <Encoding as ValueEnum>::from_str(s, true).map_err(|_| {
+            let names: Vec<_> = Encoding::value_variants()
+                .iter()
+                .map(Encoding::as_str)
+                .collect();
+
+            Error::new(format!(
+                "unknown encoding `{}`, expected one of: {}",
+                s,
+                names.join(", ")
+            ))
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,9 +261,57 @@ mod tests {
         assert_eq!(Encoding::from_path("foo.yml"), Some(Encoding::Yaml));
         assert_eq!(Encoding::from_path("foo.json"), Some(Encoding::Json));
         assert_eq!(Encoding::from_path("foo.json5"), Some(Encoding::Json5));
+        assert_eq!(Encoding::from_path("foo.jsonc"), Some(Encoding::Jsonc));
         assert_eq!(Encoding::from_path("foo.toml"), Some(Encoding::Toml));
+        assert_eq!(Encoding::from_path("foo.plist"), Some(Encoding::Plist));
+        #[cfg(feature = "excel")]
+        assert_eq!(Encoding::from_path("foo.xlsx"), Some(Encoding::Xlsx));
         assert_eq!(Encoding::from_path("foo.bak"), None);
         assert_eq!(Encoding::from_path("foo"), None);
+        assert_eq!(Encoding::from_path("dir.json/foo"), None);
+        assert_eq!(Encoding::from_path(".json"), None);
+        // Only the last extension is considered, compressed files are not supported.
+        assert_eq!(Encoding::from_path("foo.tar.gz"), None);
+        assert_eq!(Encoding::from_path("foo.json.gz"), None);
+        assert_eq!(Encoding::from_path("foo.gz.json"), Some(Encoding::Json));
+    }
+
+    #[test]
+    fn test_encoding_from_mime() {
+        let cases = [
+            ("application/json", Some(Encoding::Json)),
+            ("application/json; charset=utf-8", Some(Encoding::Json)),
+            ("Application/JSON ; charset=UTF-8", Some(Encoding::Json)),
+            ("application/vnd.api+json", Some(Encoding::Json)),
+            ("text/yaml", Some(Encoding::Yaml)),
+            ("application/x-yaml; charset=utf-8", Some(Encoding::Yaml)),
+            ("application/toml", Some(Encoding::Toml)),
+            ("text/csv; header=present", Some(Encoding::Csv)),
+            ("application/atom+xml", Some(Encoding::Xml)),
+            ("text/plain", Some(Encoding::Text)),
+            ("application/x-plist", Some(Encoding::Plist)),
+            ("application/octet-stream", None),
+            ("application/gzip", None),
+            ("", None),
+        ];
+
+        for (mime, expected) in cases {
+            assert_eq!(Encoding::from_mime(mime), expected, "{}", mime);
+        }
+    }
+
+    #[test]
+    fn test_encoding_from_str() {
+        assert_eq!("YAML".parse::<Encoding>().unwrap(), Encoding::Yaml);
+        assert_eq!("Yaml".parse::<Encoding>().unwrap(), Encoding::Yaml);
+        assert_eq!("yml".parse::<Encoding>().unwrap(), Encoding::Yaml);
+        assert_eq!("json5".parse::<Encoding>().unwrap(), Encoding::Json5);
+        assert_eq!("QS".parse::<Encoding>().unwrap(), Encoding::QueryString);
+
+        let err = "yamll".parse::<Encoding>().unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("unknown encoding `yamll`, expected one of: json, yaml, toml"));
     }
 
     #[test]
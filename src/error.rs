@@ -160,6 +160,25 @@ impl From<hcl::Error> for Error {
     }
 }
 
+impl From<plist::Error> for Error {
+    fn from(err: plist::Error) -> Self {
+        match err.into_io() {
+            Ok(io_err) => Error::io(io_err),
+            Err(err) => Error::serde(err),
+        }
+    }
+}
+
+#[cfg(feature = "excel")]
+impl From<calamine::XlsxError> for Error {
+    fn from(err: calamine::XlsxError) -> Self {
+        match err {
+            calamine::XlsxError::Io(io_err) => Error::io(io_err),
+            other => Error::serde(other),
+        }
+    }
+}
+
 impl From<ureq::Error> for Error {
     fn from(err: ureq::Error) -> Self {
         Error::RequestError(Box::new(err))
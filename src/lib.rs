@@ -1,7 +1,10 @@
 #![doc = include_str!("../README.md")]
 #![warn(missing_docs)]
 
+use de::{DeserializeOptions, Deserializer};
+use ser::{SerializeOptions, Serializer};
 use std::fs::canonicalize;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 
 pub use encoding::*;
@@ -12,13 +15,58 @@ pub use source::{Source, SourceReader};
 pub mod de;
 mod encoding;
 mod error;
+pub mod fetch;
 pub mod filter;
 pub mod key;
 mod parsers;
+pub mod schema;
 pub mod ser;
 mod sink;
 mod source;
-mod value;
+pub mod value;
+mod yaml;
+
+/// Deserializes `input` from the `from` encoding and serializes it into the `to` encoding.
+///
+/// This is a shorthand for wiring a `Deserializer` and a `Serializer` together.
+///
+/// ## Example
+///
+/// ```
+/// use dts::{de::DeserializeOptions, ser::SerializeOptions, transcode, Encoding};
+/// # use std::error::Error;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let input = r#"{"foo": ["bar"]}"#.as_bytes();
+///
+/// let output = transcode(
+///     input,
+///     Encoding::Json,
+///     Encoding::Yaml,
+///     DeserializeOptions::new(),
+///     SerializeOptions::new(),
+/// )?;
+///
+/// assert_eq!(String::from_utf8(output)?, "---\nfoo:\n- bar\n");
+/// #     Ok(())
+/// # }
+/// ```
+pub fn transcode<R>(
+    input: R,
+    from: Encoding,
+    to: Encoding,
+    de_opts: DeserializeOptions,
+    ser_opts: SerializeOptions,
+) -> Result<Vec<u8>>
+where
+    R: Read,
+{
+    let value = Deserializer::with_options(input, de_opts).deserialize(from)?;
+
+    let mut buf = Vec::new();
+    Serializer::with_options(&mut buf, ser_opts).serialize(to, value)?;
+    Ok(buf)
+}
 
 trait PathExt {
     fn relative_to<P>(&self, path: P) -> Option<PathBuf>
@@ -58,3 +106,52 @@ where
             .collect()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_transcode() {
+        let output = transcode(
+            r#"{"foo": {"bar": [1, 2]}}"#.as_bytes(),
+            Encoding::Json,
+            Encoding::Yaml,
+            DeserializeOptions::new(),
+            SerializeOptions::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "---\nfoo:\n  bar:\n  - 1\n  - 2\n"
+        );
+
+        let mut de_opts = DeserializeOptions::new();
+        de_opts.csv_headers_as_keys = true;
+        let mut ser_opts = SerializeOptions::new();
+        ser_opts.compact = Some(true);
+
+        let output = transcode(
+            "name,age\nfoo,1\nbar,2".as_bytes(),
+            Encoding::Csv,
+            Encoding::Json,
+            de_opts,
+            ser_opts,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"[{"name":"foo","age":"1"},{"name":"bar","age":"2"}]"#
+        );
+
+        assert!(transcode(
+            "{".as_bytes(),
+            Encoding::Json,
+            Encoding::Yaml,
+            DeserializeOptions::new(),
+            SerializeOptions::new(),
+        )
+        .is_err());
+    }
+}
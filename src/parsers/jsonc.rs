@@ -0,0 +1,90 @@
+/// Converts JSON with comments into plain JSON.
+///
+/// Line comments (`// ...`) and block comments (`/* ... */`) outside of strings are replaced with
+/// whitespace and trailing commas before closing brackets and braces are removed. Newlines are
+/// preserved so that line numbers in error messages of the JSON parser still match the input.
+pub fn strip(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    let mut trailing_comma = None;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                trailing_comma = None;
+                out.push(c);
+
+                while let Some(c) = chars.next() {
+                    out.push(c);
+
+                    match c {
+                        '\\' => out.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push(c);
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                out.push(' ');
+
+                while let Some(c) = chars.next() {
+                    match c {
+                        '*' if chars.peek() == Some(&'/') => {
+                            chars.next();
+                            break;
+                        }
+                        '\n' => out.push(c),
+                        _ => {}
+                    }
+                }
+            }
+            ',' => {
+                trailing_comma = Some(out.len());
+                out.push(c);
+            }
+            ']' | '}' => {
+                if let Some(pos) = trailing_comma.take() {
+                    out.replace_range(pos..pos + 1, " ");
+                }
+                out.push(c);
+            }
+            c if c.is_whitespace() => out.push(c),
+            c => {
+                trailing_comma = None;
+                out.push(c);
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_strip() {
+        assert_eq!(strip(r#"{"a": 1} // comment"#), r#"{"a": 1} "#);
+        assert_eq!(strip("{/* a */\"a\": 1}"), "{ \"a\": 1}");
+        assert_eq!(strip("/* a\nb */1"), " \n1");
+        assert_eq!(
+            strip(r#"{"url": "http://x/*y*/", "q": "\"//\""}"#),
+            r#"{"url": "http://x/*y*/", "q": "\"//\""}"#
+        );
+        assert_eq!(strip("[1, 2, ]"), "[1, 2  ]");
+        assert_eq!(strip("{\"a\": [1,],\n}"), "{\"a\": [1 ] \n}");
+        assert_eq!(strip("[1, // one\n]"), "[1  \n]");
+        assert_eq!(strip(r#"[",]"]"#), r#"[",]"]"#);
+    }
+}
@@ -1,5 +1,6 @@
 mod error;
 pub mod flat_key;
 pub mod gron;
+pub mod jsonc;
 
 pub use error::*;
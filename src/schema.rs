@@ -0,0 +1,247 @@
+//! Infer JSON Schema documents from `serde_json::Value`s.
+
+use serde_json::{json, Map, Value};
+
+/// The JSON Schema dialect of the inferred schemas.
+pub const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Infers a JSON Schema (draft 2020-12) document that describes the structure of `value`.
+///
+/// The inferred schema uses the `type`, `properties`, `required`, `items` and `anyOf` keywords.
+/// Object properties are only marked as required if they are present in all observed objects at
+/// the same position, e.g. in all elements of an array. Array elements of different types are
+/// described using `anyOf`.
+///
+/// ## Example
+///
+/// ```
+/// # use pretty_assertions::assert_eq;
+/// use dts::schema::infer;
+/// use serde_json::json;
+///
+/// let value = json!([{"id": 1, "name": "foo"}, {"id": 2}]);
+///
+/// assert_eq!(
+///     infer(&value),
+///     json!({
+///         "$schema": "https://json-schema.org/draft/2020-12/schema",
+///         "type": "array",
+///         "items": {
+///             "type": "object",
+///             "properties": {
+///                 "id": {"type": "integer"},
+///                 "name": {"type": "string"}
+///             },
+///             "required": ["id"]
+///         }
+///     })
+/// );
+/// ```
+pub fn infer(value: &Value) -> Value {
+    let mut schema = Map::new();
+    schema.insert("$schema".into(), DIALECT.into());
+
+    if let Value::Object(inferred) = infer_schema(value) {
+        schema.extend(inferred);
+    }
+
+    Value::Object(schema)
+}
+
+fn infer_schema(value: &Value) -> Value {
+    match value {
+        Value::Null => json!({"type": "null"}),
+        Value::Bool(_) => json!({"type": "boolean"}),
+        Value::Number(n) if n.is_f64() => json!({"type": "number"}),
+        Value::Number(_) => json!({"type": "integer"}),
+        Value::String(_) => json!({"type": "string"}),
+        Value::Array(array) => {
+            let mut schema = json!({"type": "array"});
+
+            if let Some(items) = array.iter().map(infer_schema).reduce(merge) {
+                schema["items"] = items;
+            }
+
+            schema
+        }
+        Value::Object(object) => {
+            let properties: Map<String, Value> = object
+                .iter()
+                .map(|(key, value)| (key.clone(), infer_schema(value)))
+                .collect();
+            let required: Vec<Value> = object.keys().cloned().map(Value::String).collect();
+
+            json!({"type": "object", "properties": properties, "required": required})
+        }
+    }
+}
+
+// Merges two inferred schemas into one that describes the values of both.
+fn merge(lhs: Value, rhs: Value) -> Value {
+    let mut variants = variants(rhs)
+        .into_iter()
+        .fold(variants(lhs), |mut variants, rhs| {
+            match variants.iter().position(|lhs| compatible(lhs, &rhs)) {
+                Some(pos) => {
+                    let lhs = variants.remove(pos);
+                    variants.insert(pos, merge_compatible(lhs, rhs));
+                }
+                None => variants.push(rhs),
+            }
+            variants
+        });
+
+    if variants.len() == 1 {
+        variants.remove(0)
+    } else {
+        json!({ "anyOf": variants })
+    }
+}
+
+// Returns the `anyOf` variants of a schema or the schema itself if it has none.
+fn variants(schema: Value) -> Vec<Value> {
+    match schema {
+        Value::Object(mut object) => match object.remove("anyOf") {
+            Some(Value::Array(variants)) => variants,
+            _ => vec![Value::Object(object)],
+        },
+        schema => vec![schema],
+    }
+}
+
+// Returns `true` if both schemas can be merged without `anyOf`.
+fn compatible(lhs: &Value, rhs: &Value) -> bool {
+    match (lhs["type"].as_str(), rhs["type"].as_str()) {
+        (Some("integer" | "number"), Some("integer" | "number")) => true,
+        (lhs, rhs) => lhs == rhs,
+    }
+}
+
+fn merge_compatible(mut lhs: Value, rhs: Value) -> Value {
+    match lhs["type"].as_str() {
+        Some("integer") if rhs["type"] == "number" => rhs,
+        Some("array") => {
+            if let Some(items) = rhs.get("items") {
+                lhs["items"] = match lhs.get("items") {
+                    Some(lhs_items) => merge(lhs_items.clone(), items.clone()),
+                    None => items.clone(),
+                };
+            }
+
+            lhs
+        }
+        Some("object") => {
+            let required: Vec<Value> = lhs["required"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|key| rhs["required"].as_array().unwrap().contains(key))
+                .cloned()
+                .collect();
+
+            let properties = lhs["properties"].as_object_mut().unwrap();
+
+            for (key, schema) in rhs["properties"].as_object().unwrap() {
+                match properties.get_mut(key) {
+                    Some(existing) => *existing = merge(existing.take(), schema.clone()),
+                    None => {
+                        properties.insert(key.clone(), schema.clone());
+                    }
+                }
+            }
+
+            lhs["required"] = Value::Array(required);
+            lhs
+        }
+        _ => lhs,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // Validates `value` against the subset of JSON Schema keywords produced by `infer`.
+    fn validate(schema: &Value, value: &Value) -> bool {
+        if let Some(variants) = schema["anyOf"].as_array() {
+            return variants.iter().any(|schema| validate(schema, value));
+        }
+
+        match (schema["type"].as_str(), value) {
+            (Some("null"), Value::Null) | (Some("boolean"), Value::Bool(_)) => true,
+            (Some("string"), Value::String(_)) | (Some("number"), Value::Number(_)) => true,
+            (Some("integer"), Value::Number(n)) => !n.is_f64(),
+            (Some("array"), Value::Array(array)) => match schema.get("items") {
+                Some(items) => array.iter().all(|value| validate(items, value)),
+                None => true,
+            },
+            (Some("object"), Value::Object(object)) => {
+                let required = schema["required"].as_array().unwrap();
+                let properties = schema["properties"].as_object().unwrap();
+
+                required
+                    .iter()
+                    .all(|key| object.contains_key(key.as_str().unwrap()))
+                    && object.iter().all(|(key, value)| match properties.get(key) {
+                        Some(schema) => validate(schema, value),
+                        None => true,
+                    })
+            }
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn test_infer() {
+        let value = json!({
+            "name": "dts",
+            "version": 1.5,
+            "tags": ["a", 1, 2.5, null],
+            "users": [
+                {"id": 1, "email": "a@example.com", "roles": []},
+                {"id": 2, "roles": ["admin"]}
+            ]
+        });
+
+        let schema = infer(&value);
+
+        assert_eq!(
+            schema,
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string"},
+                    "version": {"type": "number"},
+                    "tags": {
+                        "type": "array",
+                        "items": {
+                            "anyOf": [
+                                {"type": "string"},
+                                {"type": "number"},
+                                {"type": "null"}
+                            ]
+                        }
+                    },
+                    "users": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "id": {"type": "integer"},
+                                "email": {"type": "string"},
+                                "roles": {"type": "array", "items": {"type": "string"}}
+                            },
+                            "required": ["id", "roles"]
+                        }
+                    }
+                },
+                "required": ["name", "version", "tags", "users"]
+            })
+        );
+
+        assert!(validate(&schema, &value));
+        assert!(!validate(&schema, &json!({"name": "dts"})));
+    }
+}
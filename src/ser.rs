@@ -1,8 +1,10 @@
 //! This module provides a `Serializer` which supports serializing values into various output
 //! encodings.
 
-use crate::{key::flatten_keys, value::ValueExt, Encoding, Error, Result};
-use serde_json::Value;
+use crate::{key::flatten_keys, value::ValueExt, yaml, Encoding, Error, Result};
+use clap::ValueEnum;
+use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::fmt::Write;
 
 /// Options for the `Serializer`. The options are context specific and may only be honored when
@@ -10,8 +12,8 @@ use std::fmt::Write;
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct SerializeOptions {
     /// Emit output data in a compact format. This will disable pretty printing for encodings that
-    /// support it.
-    pub compact: bool,
+    /// support it. If `None`, the output is pretty printed.
+    pub compact: Option<bool>,
     /// Append a trailing newline to the serialized data.
     pub newline: bool,
     /// When the input is an array of objects and the output encoding is CSV, the field names of
@@ -21,8 +23,23 @@ pub struct SerializeOptions {
     pub keys_as_csv_headers: bool,
     /// Optional custom delimiter for CSV output.
     pub csv_delimiter: Option<u8>,
+    /// Controls how newlines embedded in CSV fields are handled.
+    pub csv_newline_policy: CsvNewlinePolicy,
     /// Optional seprator to join text output with.
     pub text_join_separator: Option<String>,
+    /// Escape control characters other than newline and tab in text output as `\xNN`.
+    pub text_escape_control: bool,
+    /// Emit JSON output according to the JSON Canonicalization Scheme (JCS) as defined in RFC
+    /// 8785. This implies compact output.
+    pub jcs: bool,
+    /// Optional key to wrap values under when serializing to TOML if they are not a table (e.g.
+    /// arrays or scalars), since TOML documents must have a table as root.
+    pub toml_root_key: Option<String>,
+    /// Serialize objects of the form `{"__tag__": "!Tag", "value": <value>}` into tagged YAML
+    /// values.
+    pub preserve_yaml_tags: bool,
+    /// Controls how string values are quoted in gron output.
+    pub gron_quote: GronQuote,
 }
 
 impl SerializeOptions {
@@ -32,6 +49,45 @@ impl SerializeOptions {
     }
 }
 
+/// Controls how newlines embedded in CSV fields are handled.
+#[derive(ValueEnum, Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum CsvNewlinePolicy {
+    /// Keep newlines and quote the field as required by RFC 4180.
+    #[default]
+    Quote,
+    /// Replace carriage returns and newlines with the escape sequences `\r` and `\n`.
+    Escape,
+    /// Remove carriage returns and newlines.
+    Strip,
+}
+
+impl CsvNewlinePolicy {
+    fn apply(self, field: String) -> String {
+        if !field.contains(['\r', '\n']) {
+            return field;
+        }
+
+        match self {
+            CsvNewlinePolicy::Quote => field,
+            CsvNewlinePolicy::Escape => field.replace('\r', "\\r").replace('\n', "\\n"),
+            CsvNewlinePolicy::Strip => field.replace(['\r', '\n'], ""),
+        }
+    }
+}
+
+/// Controls how string values are quoted in gron output. Values of other types are always
+/// written as JSON.
+#[derive(ValueEnum, Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum GronQuote {
+    /// Quote strings as JSON. Only this style can be deserialized again.
+    #[default]
+    Json,
+    /// Quote strings with single quotes like a POSIX shell would, e.g. `'it'\''s'`.
+    Single,
+    /// Write strings without any quoting or escaping.
+    None,
+}
+
 /// A `SerializerBuilder` can be used to build a `Serializer` with certain
 /// `SerializeOptions`.
 ///
@@ -59,7 +115,13 @@ impl SerializerBuilder {
     /// Emit output data in a compact format. This will disable pretty printing for encodings that
     /// support it.
     pub fn compact(&mut self, yes: bool) -> &mut Self {
-        self.opts.compact = yes;
+        self.opts.compact = Some(yes);
+        self
+    }
+
+    /// Pretty print output data for encodings that support it. This is the inverse of `compact`.
+    pub fn pretty(&mut self, yes: bool) -> &mut Self {
+        self.opts.compact = Some(!yes);
         self
     }
 
@@ -84,6 +146,12 @@ impl SerializerBuilder {
         self
     }
 
+    /// Controls how newlines embedded in CSV fields are handled.
+    pub fn csv_newline_policy(&mut self, policy: CsvNewlinePolicy) -> &mut Self {
+        self.opts.csv_newline_policy = policy;
+        self
+    }
+
     /// Sets a custom separator to join text output with.
     pub fn text_join_separator<S>(&mut self, sep: S) -> &mut Self
     where
@@ -93,6 +161,41 @@ impl SerializerBuilder {
         self
     }
 
+    /// Escape control characters other than newline and tab in text output.
+    pub fn text_escape_control(&mut self, yes: bool) -> &mut Self {
+        self.opts.text_escape_control = yes;
+        self
+    }
+
+    /// Emit JSON output according to the JSON Canonicalization Scheme (JCS) as defined in RFC
+    /// 8785.
+    pub fn jcs(&mut self, yes: bool) -> &mut Self {
+        self.opts.jcs = yes;
+        self
+    }
+
+    /// Sets the key to wrap non-table values under when serializing to TOML.
+    pub fn toml_root_key<S>(&mut self, key: S) -> &mut Self
+    where
+        S: AsRef<str>,
+    {
+        self.opts.toml_root_key = Some(key.as_ref().to_owned());
+        self
+    }
+
+    /// Controls how string values are quoted in gron output.
+    pub fn gron_quote(&mut self, quote: GronQuote) -> &mut Self {
+        self.opts.gron_quote = quote;
+        self
+    }
+
+    /// Serialize objects of the form `{"__tag__": "!Tag", "value": <value>}` into tagged YAML
+    /// values.
+    pub fn preserve_yaml_tags(&mut self, yes: bool) -> &mut Self {
+        self.opts.preserve_yaml_tags = yes;
+        self
+    }
+
     /// Builds the `Serializer` for the given writer.
     pub fn build<W>(&self, writer: W) -> Serializer<W>
     where
@@ -143,7 +246,7 @@ where
     pub fn serialize(&mut self, encoding: Encoding, value: Value) -> Result<()> {
         match encoding {
             Encoding::Yaml => self.serialize_yaml(value)?,
-            Encoding::Json => self.serialize_json(value)?,
+            Encoding::Json | Encoding::Jsonc => self.serialize_json(value)?,
             Encoding::Toml => self.serialize_toml(value)?,
             Encoding::Csv => self.serialize_csv(value)?,
             Encoding::QueryString => self.serialize_query_string(value)?,
@@ -151,6 +254,8 @@ where
             Encoding::Text => self.serialize_text(value)?,
             Encoding::Gron => self.serialize_gron(value)?,
             Encoding::Hcl => self.serialize_hcl(value)?,
+            Encoding::Plist => self.serialize_plist(value)?,
+            Encoding::Shell => self.serialize_shell(value)?,
             encoding => return Err(Error::UnsupportedEncoding(encoding)),
         };
 
@@ -161,13 +266,29 @@ where
         Ok(())
     }
 
+    fn compact(&self) -> bool {
+        self.opts.compact.unwrap_or(false)
+    }
+
     fn serialize_yaml(&mut self, value: Value) -> Result<()> {
         self.writer.write_all(b"---\n")?;
-        Ok(serde_yaml::to_writer(&mut self.writer, &value)?)
+
+        if self.opts.preserve_yaml_tags {
+            Ok(serde_yaml::to_writer(
+                &mut self.writer,
+                &yaml::from_json(value),
+            )?)
+        } else {
+            Ok(serde_yaml::to_writer(&mut self.writer, &value)?)
+        }
     }
 
     fn serialize_json(&mut self, value: Value) -> Result<()> {
-        if self.opts.compact {
+        if self.opts.jcs {
+            let mut buf = String::new();
+            write_jcs(&mut buf, &value)?;
+            self.writer.write_all(buf.as_bytes())?
+        } else if self.compact() {
             serde_json::to_writer(&mut self.writer, &value)?
         } else {
             serde_json::to_writer_pretty(&mut self.writer, &value)?
@@ -177,9 +298,14 @@ where
     }
 
     fn serialize_toml(&mut self, value: Value) -> Result<()> {
+        let value = match (&self.opts.toml_root_key, value) {
+            (Some(key), value) if !value.is_object() => json!({ key: value }),
+            (_, value) => value,
+        };
+
         let value = toml::Value::try_from(value)?;
 
-        let s = if self.opts.compact {
+        let s = if self.compact() {
             toml::ser::to_string(&value)?
         } else {
             toml::ser::to_string_pretty(&value)?
@@ -200,12 +326,14 @@ where
 
             let mut headers: Option<Vec<String>> = None;
             let empty_value = Value::String("".into());
+            let newline_policy = self.opts.csv_newline_policy;
 
             for row in value.into_array().into_iter() {
                 let row_data = if !self.opts.keys_as_csv_headers {
                     row.into_array()
                         .into_iter()
                         .map(Value::into_string)
+                        .map(|field| newline_policy.apply(field))
                         .collect::<Vec<_>>()
                 } else {
                     let row = row.into_object("csv");
@@ -224,6 +352,7 @@ where
                         .map(|header| row.get(header).unwrap_or(&empty_value))
                         .cloned()
                         .map(Value::into_string)
+                        .map(|field| newline_policy.apply(field))
                         .collect::<Vec<_>>()
                 };
 
@@ -242,6 +371,10 @@ where
         Ok(serde_xml_rs::to_writer(&mut self.writer, &value)?)
     }
 
+    fn serialize_plist(&mut self, value: Value) -> Result<()> {
+        Ok(plist::to_writer_xml(&mut self.writer, &value)?)
+    }
+
     fn serialize_text(&mut self, value: Value) -> Result<()> {
         let sep = self
             .opts
@@ -249,10 +382,19 @@ where
             .clone()
             .unwrap_or_else(|| String::from('\n'));
 
+        let escape_control = self.opts.text_escape_control;
+
         let text = value
             .into_array()
             .into_iter()
             .map(Value::into_string)
+            .map(|s| {
+                if escape_control {
+                    escape_control_chars(&s)
+                } else {
+                    s
+                }
+            })
             .collect::<Vec<String>>()
             .join(&sep);
 
@@ -260,22 +402,69 @@ where
     }
 
     fn serialize_gron(&mut self, value: Value) -> Result<()> {
+        let quote = self.opts.gron_quote;
+
         let output = flatten_keys(value, "json")
             .as_object()
             .unwrap()
             .into_iter()
             .fold(String::new(), |mut output, (k, v)| {
-                let _ = writeln!(output, "{k} = {v};");
+                let _ = match (quote, v) {
+                    (GronQuote::Single, Value::String(s)) => {
+                        writeln!(output, "{k} = '{}';", s.replace('\'', "'\\''"))
+                    }
+                    (GronQuote::None, Value::String(s)) => writeln!(output, "{k} = {s};"),
+                    (_, v) => writeln!(output, "{k} = {v};"),
+                };
                 output
             });
 
         Ok(self.writer.write_all(output.as_bytes())?)
     }
 
+    fn serialize_shell(&mut self, value: Value) -> Result<()> {
+        if !value.is_object() {
+            return Err(Error::new("shell output requires an object"));
+        }
+
+        let mut names = HashMap::new();
+        let mut output = String::new();
+
+        for (k, v) in flatten_keys(value, "").as_object().unwrap() {
+            if v.is_array() || v.is_object() {
+                continue;
+            }
+
+            let name = shell_var_name(k);
+
+            if name.is_empty() {
+                return Err(Error::new(format!(
+                    "key `{k}` cannot be converted into a shell variable name"
+                )));
+            }
+
+            if let Some(other) = names.insert(name.clone(), k) {
+                return Err(Error::new(format!(
+                    "keys `{other}` and `{k}` both map to shell variable `{name}`"
+                )));
+            }
+
+            let value = match v {
+                Value::String(s) => s.clone(),
+                Value::Null => String::new(),
+                v => v.to_string(),
+            };
+
+            let _ = writeln!(output, "{}='{}'", name, value.replace('\'', "'\\''"));
+        }
+
+        Ok(self.writer.write_all(output.as_bytes())?)
+    }
+
     fn serialize_hcl(&mut self, value: Value) -> Result<()> {
-        if self.opts.compact {
+        if self.compact() {
             let fmt = hcl::format::Formatter::builder()
-                .compact(self.opts.compact)
+                .compact(true)
                 .build(&mut self.writer);
             let mut ser = hcl::ser::Serializer::with_formatter(fmt);
             ser.serialize(&value)?;
@@ -287,6 +476,123 @@ where
     }
 }
 
+// Converts a flat key like `foo.bar[0]` into a shell variable name like `FOO_BAR_0`. Names must not
+// start with a digit, so these are prefixed with an underscore.
+fn shell_var_name(key: &str) -> String {
+    let name = key
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+        .to_ascii_uppercase();
+
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+// Escapes control characters except for newline and tab as `\xNN`. All characters of the Unicode
+// `Cc` category are below U+0100, so two hex digits are always sufficient.
+fn escape_control_chars(s: &str) -> String {
+    s.chars()
+        .fold(String::with_capacity(s.len()), |mut out, c| {
+            if c.is_control() && c != '\n' && c != '\t' {
+                let _ = write!(out, "\\x{:02x}", c as u32);
+            } else {
+                out.push(c);
+            }
+            out
+        })
+}
+
+// Writes `value` in the canonical form described by RFC 8785: no whitespace, object keys sorted by
+// their UTF-16 code units and numbers formatted like ECMAScript's `Number.prototype.toString`. The
+// string escaping rules of RFC 8785 match the ones of `serde_json`.
+fn write_jcs(buf: &mut String, value: &Value) -> Result<()> {
+    match value {
+        Value::Null => buf.push_str("null"),
+        Value::Bool(b) => buf.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => {
+            let f = n
+                .as_f64()
+                .ok_or_else(|| Error::new(format!("number `{}` is not representable as f64", n)))?;
+            buf.push_str(&jcs_number(f));
+        }
+        Value::String(s) => buf.push_str(&serde_json::to_string(s)?),
+        Value::Array(array) => {
+            buf.push('[');
+            for (i, value) in array.iter().enumerate() {
+                if i > 0 {
+                    buf.push(',');
+                }
+                write_jcs(buf, value)?;
+            }
+            buf.push(']');
+        }
+        Value::Object(object) => {
+            let mut entries: Vec<_> = object.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+            buf.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    buf.push(',');
+                }
+                buf.push_str(&serde_json::to_string(key)?);
+                buf.push(':');
+                write_jcs(buf, value)?;
+            }
+            buf.push('}');
+        }
+    }
+
+    Ok(())
+}
+
+// Formats a number according to the ECMAScript `Number.prototype.toString` algorithm. The number of
+// digits of the shortest round-trip representation is obtained from the `LowerExp` implementation
+// of `f64`. The number is then formatted again with exactly that many digits, because ECMAScript
+// requires ties to be resolved to the even digit.
+fn jcs_number(f: f64) -> String {
+    if f == 0.0 {
+        // Also covers negative zero.
+        return String::from("0");
+    }
+
+    let shortest = format!("{:e}", f.abs());
+    let precision = shortest.split_once('e').unwrap().0.replace('.', "").len() - 1;
+    let sci = format!("{:.*e}", precision, f.abs());
+    let (mantissa, exp) = sci.split_once('e').unwrap();
+    let digits = mantissa.replace('.', "");
+    let k = digits.len() as i32;
+    let n = exp.parse::<i32>().unwrap() + 1;
+
+    let s = if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat(-n as usize), digits)
+    } else {
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        let mantissa = if k == 1 {
+            digits
+        } else {
+            format!("{}.{}", &digits[..1], &digits[1..])
+        };
+
+        format!("{}e{}{}", mantissa, sign, (n - 1).abs())
+    };
+
+    if f < 0.0 {
+        format!("-{}", s)
+    } else {
+        s
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -316,7 +622,7 @@ mod test {
     #[test]
     fn test_serialize_json() {
         assert_builder_serializes_to(
-            &mut SerializerBuilder::new().compact(true),
+            SerializerBuilder::new().compact(true),
             Encoding::Json,
             json!(["one", "two"]),
             "[\"one\",\"two\"]",
@@ -326,6 +632,12 @@ mod test {
             json!(["one", "two"]),
             "[\n  \"one\",\n  \"two\"\n]",
         );
+        assert_builder_serializes_to(
+            SerializerBuilder::new().compact(true).pretty(true),
+            Encoding::Json,
+            json!(["one", "two"]),
+            "[\n  \"one\",\n  \"two\"\n]",
+        );
     }
 
     #[test]
@@ -336,7 +648,7 @@ mod test {
             "one,two\nthree,four\n",
         );
         assert_builder_serializes_to(
-            &mut SerializerBuilder::new().keys_as_csv_headers(true),
+            SerializerBuilder::new().keys_as_csv_headers(true),
             Encoding::Csv,
             json!([
                 {"one": "val1", "two": "val2"},
@@ -346,7 +658,7 @@ mod test {
             "one,two\nval1,val2\nval3,\n,val5\n",
         );
         assert_builder_serializes_to(
-            &mut SerializerBuilder::new().keys_as_csv_headers(true),
+            SerializerBuilder::new().keys_as_csv_headers(true),
             Encoding::Csv,
             json!({"one": "val1", "two": "val2"}),
             "one,two\nval1,val2\n",
@@ -358,13 +670,36 @@ mod test {
             "\"{\"\"non-array\"\":\"\"row\"\"}\"\n",
         );
         assert_builder_serializes_to(
-            &mut SerializerBuilder::new().keys_as_csv_headers(true),
+            SerializerBuilder::new().keys_as_csv_headers(true),
             Encoding::Csv,
             json!([["non-object-row"]]),
             "csv\n\"[\"\"non-object-row\"\"]\"\n",
         );
     }
 
+    #[test]
+    fn test_serialize_csv_newline_policy() {
+        let value = json!([["multi\nline", "crlf\r\nline", "single"]]);
+
+        assert_serializes_to(
+            Encoding::Csv,
+            value.clone(),
+            "\"multi\nline\",\"crlf\r\nline\",single\n",
+        );
+        assert_builder_serializes_to(
+            SerializerBuilder::new().csv_newline_policy(CsvNewlinePolicy::Escape),
+            Encoding::Csv,
+            value.clone(),
+            "multi\\nline,crlf\\r\\nline,single\n",
+        );
+        assert_builder_serializes_to(
+            SerializerBuilder::new().csv_newline_policy(CsvNewlinePolicy::Strip),
+            Encoding::Csv,
+            value,
+            "multiline,crlfline,single\n",
+        );
+    }
+
     #[test]
     fn test_serialize_text() {
         assert_serializes_to(Encoding::Text, json!(["one", "two"]), "one\ntwo");
@@ -376,6 +711,78 @@ mod test {
         assert_serializes_to(Encoding::Text, json!({"foo": "bar"}), "{\"foo\":\"bar\"}");
     }
 
+    #[test]
+    fn test_serialize_text_escape_control() {
+        let value = json!(["bell\u{7}", "\u{1b}[31mred\tline\nbreak"]);
+
+        assert_serializes_to(
+            Encoding::Text,
+            value.clone(),
+            "bell\u{7}\n\u{1b}[31mred\tline\nbreak",
+        );
+        assert_builder_serializes_to(
+            SerializerBuilder::new().text_escape_control(true),
+            Encoding::Text,
+            value,
+            "bell\\x07\n\\x1b[31mred\tline\nbreak",
+        );
+    }
+
+    #[test]
+    fn test_serialize_gron() {
+        let value = json!({"foo": "it's", "bar": [1, null], "baz": {}});
+
+        assert_serializes_to(
+            Encoding::Gron,
+            value.clone(),
+            "json = {};\njson.bar = [];\njson.bar[0] = 1;\njson.bar[1] = null;\njson.baz = {};\njson.foo = \"it's\";\n",
+        );
+
+        // The default style can be deserialized again.
+        let mut buf = Vec::new();
+        let mut ser = SerializerBuilder::new().build(&mut buf);
+        ser.serialize(Encoding::Gron, value.clone()).unwrap();
+        let mut de = crate::de::DeserializerBuilder::new().build(buf.as_slice());
+        assert_eq!(de.deserialize(Encoding::Gron).unwrap()["json"], value);
+
+        assert_builder_serializes_to(
+            SerializerBuilder::new().gron_quote(GronQuote::Single),
+            Encoding::Gron,
+            value.clone(),
+            "json = {};\njson.bar = [];\njson.bar[0] = 1;\njson.bar[1] = null;\njson.baz = {};\njson.foo = 'it'\\''s';\n",
+        );
+        assert_builder_serializes_to(
+            SerializerBuilder::new().gron_quote(GronQuote::None),
+            Encoding::Gron,
+            value,
+            "json = {};\njson.bar = [];\njson.bar[0] = 1;\njson.bar[1] = null;\njson.baz = {};\njson.foo = it's;\n",
+        );
+    }
+
+    #[test]
+    fn test_serialize_shell() {
+        assert_serializes_to(
+            Encoding::Shell,
+            json!({
+                "name": "it's",
+                "db": {"host-name": "localhost", "port": 5432},
+                "tags": ["a", null],
+                "1st": true
+            }),
+            "_1ST='true'\nDB_PORT='5432'\nDB_HOST_NAME='localhost'\nNAME='it'\\''s'\nTAGS_0='a'\nTAGS_1=''\n",
+        );
+
+        let mut ser = Serializer::new(Vec::new());
+        assert!(ser.serialize(Encoding::Shell, json!(["foo"])).is_err());
+        assert!(ser
+            .serialize(Encoding::Shell, json!({"\u{e9}": 1}))
+            .is_err());
+        assert!(ser.serialize(Encoding::Shell, json!({"": 4})).is_err());
+        assert!(ser
+            .serialize(Encoding::Shell, json!({"a b": 2, "a_b": 3}))
+            .is_err());
+    }
+
     #[test]
     fn test_serialize_hcl() {
         assert_serializes_to(Encoding::Hcl, json!([{"foo": "bar"}]), "foo = \"bar\"\n");
@@ -385,4 +792,152 @@ mod test {
             "foo = \"bar\"\nbar = 2\n",
         );
     }
+
+    #[test]
+    fn test_serialize_json_jcs() {
+        let mut builder = SerializerBuilder::new();
+        builder.jcs(true);
+
+        // Examples from RFC 8785, section 3.2.2 and 3.2.3.
+        assert_builder_serializes_to(
+            &mut builder,
+            Encoding::Json,
+            json!({
+                "numbers": [333333333.3333333, 1e30, 4.50, 2e-3, 1e-27],
+                "string": "\u{20ac}$\u{000F}\u{000a}A'\u{0042}\u{0022}\u{005c}\\\"/",
+                "literals": [null, true, false]
+            }),
+            r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#,
+        );
+        assert_builder_serializes_to(
+            &mut builder,
+            Encoding::Json,
+            json!({
+                "\u{20ac}": "Euro Sign",
+                "\r": "Carriage Return",
+                "\u{fb33}": "Hebrew Letter Dalet With Dagesh",
+                "1": "One",
+                "\u{1f600}": "Emoji: Grinning Face",
+                "\u{0080}": "Control",
+                "\u{00f6}": "Latin Small Letter O With Diaeresis"
+            }),
+            concat!(
+                r#"{"\r":"Carriage Return","1":"One","#,
+                "\"\u{0080}\":\"Control\",\"\u{00f6}\":\"Latin Small Letter O With Diaeresis\",",
+                "\"\u{20ac}\":\"Euro Sign\",\"\u{1f600}\":\"Emoji: Grinning Face\",",
+                "\"\u{fb33}\":\"Hebrew Letter Dalet With Dagesh\"}"
+            ),
+        );
+    }
+
+    #[test]
+    fn test_jcs_number() {
+        // Test vectors from RFC 8785, appendix B.
+        let cases = [
+            (0x0000000000000000, "0"),
+            (0x8000000000000000, "0"),
+            (0x0000000000000001, "5e-324"),
+            (0x8000000000000001, "-5e-324"),
+            (0x7fefffffffffffff, "1.7976931348623157e+308"),
+            (0xffefffffffffffff, "-1.7976931348623157e+308"),
+            (0x4340000000000000, "9007199254740992"),
+            (0xc340000000000000, "-9007199254740992"),
+            (0x4430000000000000, "295147905179352830000"),
+            (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+            (0x44b52d02c7e14af6, "1e+23"),
+            (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+            (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+            (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+            (0x444b1ae4d6e2ef50, "1e+21"),
+            (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+            (0x3eb0c6f7a0b5ed8d, "0.000001"),
+            (0x41b3de4355555553, "333333333.3333332"),
+            (0x41b3de4355555554, "333333333.33333325"),
+            (0x41b3de4355555555, "333333333.3333333"),
+            (0x41b3de4355555556, "333333333.3333334"),
+            (0x41b3de4355555557, "333333333.33333343"),
+            (0xbecbf647612f3696, "-0.0000033333333333333333"),
+            (0x43143ff3c1cb0959, "1424953923781206.2"),
+        ];
+
+        for (bits, expected) in cases {
+            assert_eq!(jcs_number(f64::from_bits(bits)), expected);
+        }
+    }
+
+    #[test]
+    fn test_serialize_yaml_tags() {
+        let value = json!({"bucket": {"__tag__": "!Ref", "value": "MyBucket"}});
+
+        assert_serializes_to(
+            Encoding::Yaml,
+            value.clone(),
+            "---\nbucket:\n  __tag__: '!Ref'\n  value: MyBucket\n",
+        );
+        assert_builder_serializes_to(
+            SerializerBuilder::new().preserve_yaml_tags(true),
+            Encoding::Yaml,
+            value,
+            "---\nbucket: !Ref MyBucket\n",
+        );
+    }
+
+    #[test]
+    fn test_serialize_toml() {
+        assert_serializes_to(
+            Encoding::Toml,
+            json!({"float": 1.0, "int": 1, "negative": -2.0}),
+            "float = 1.0\nint = 1\nnegative = -2.0\n",
+        );
+
+        // Whole-valued floats must survive a round trip without turning into integers.
+        let value = json!({"float": 1.0, "int": 1});
+        let mut buf = Vec::new();
+        let mut ser = SerializerBuilder::new().build(&mut buf);
+        ser.serialize(Encoding::Toml, value.clone()).unwrap();
+
+        let mut de = crate::de::DeserializerBuilder::new().build(buf.as_slice());
+        let round_tripped = de.deserialize(Encoding::Toml).unwrap();
+        assert_eq!(round_tripped, value);
+        assert!(round_tripped["float"].is_f64());
+        assert!(round_tripped["int"].is_i64());
+    }
+
+    #[test]
+    fn test_serialize_toml_root_key() {
+        let mut builder = SerializerBuilder::new();
+        builder.toml_root_key("items");
+
+        assert_builder_serializes_to(
+            &mut builder,
+            Encoding::Toml,
+            json!([{"a": 1}, {"a": 2}]),
+            "[[items]]\na = 1\n\n[[items]]\na = 2\n",
+        );
+        assert_builder_serializes_to(&mut builder, Encoding::Toml, json!({"a": 1}), "a = 1\n");
+
+        let mut buf = Vec::new();
+        let mut ser = SerializerBuilder::new().build(&mut buf);
+        assert!(ser.serialize(Encoding::Toml, json!([1, 2])).is_err());
+    }
+
+    #[test]
+    fn test_serialize_plist() {
+        assert_serializes_to(
+            Encoding::Plist,
+            json!({"foo": ["bar", 1, true]}),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>foo</key>
+	<array>
+		<string>bar</string>
+		<integer>1</integer>
+		<true/>
+	</array>
+</dict>
+</plist>"#,
+        );
+    }
 }
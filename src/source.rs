@@ -1,4 +1,4 @@
-use crate::{Encoding, Error, PathExt, Result};
+use crate::{fetch::Fetchers, Encoding, Error, PathExt, Result};
 use std::fmt;
 use std::fs;
 use std::io::{self, BufRead, BufReader, Cursor, Read};
@@ -6,6 +6,9 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use url::Url;
 
+// Number of bytes that are inspected to detect binary input.
+const BINARY_CHECK_LEN: usize = 8192;
+
 /// A source for data that needs to be deserialized.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Source {
@@ -66,10 +69,27 @@ impl Source {
     /// May return an error if the source is `Source::Path` and the file cannot be opened of if
     /// source is `Source::Url` and there is an error requesting the remote url.
     pub fn to_reader(&self) -> Result<SourceReader> {
+        self.to_reader_with(&Fetchers::default())
+    }
+
+    /// Returns a `SourceReader` to read from the source. Sources of variant `Source::Url` are read
+    /// using the `Fetcher` from `fetchers` which handles the URL scheme.
+    ///
+    /// ## Errors
+    ///
+    /// May return an error if the source is `Source::Path` and the file cannot be opened of if
+    /// source is `Source::Url` and there is no fetcher for its scheme or fetching fails.
+    pub fn to_reader_with(&self, fetchers: &Fetchers) -> Result<SourceReader> {
         let reader: Box<dyn io::Read> = match self {
             Self::Stdin => Box::new(io::stdin()),
             Self::Path(path) => Box::new(fs::File::open(path)?),
-            Self::Url(url) => Box::new(ureq::get(url.as_ref()).call()?.into_reader()),
+            Self::Url(url) => {
+                let fetched = fetchers.fetch(url)?;
+                // The encoding hint provided by the fetcher (e.g. from the HTTP `Content-Type`
+                // header) is only used if the encoding cannot be inferred from the URL path.
+                let encoding = self.encoding().or(fetched.encoding);
+                return SourceReader::new(fetched.reader, encoding);
+            }
         };
 
         SourceReader::new(reader, self.encoding())
@@ -164,6 +184,34 @@ impl SourceReader {
                 .and_then(Encoding::from_first_line)
         })
     }
+
+    /// Returns `true` if the beginning of the input looks like binary data, that is, if it
+    /// contains NUL bytes or invalid UTF-8.
+    ///
+    /// Only the first line and the currently buffered remainder are inspected, no additional
+    /// data is consumed from the underlying reader.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if filling the read buffer fails.
+    pub fn is_binary(&mut self) -> Result<bool> {
+        let first_line = self.first_line.get_ref();
+        let mut head = first_line[..first_line.len().min(BINARY_CHECK_LEN)].to_vec();
+
+        if head.len() < BINARY_CHECK_LEN {
+            let buf = self.remainder.fill_buf()?;
+            let len = buf.len().min(BINARY_CHECK_LEN - head.len());
+            head.extend_from_slice(&buf[..len]);
+        }
+
+        if head.contains(&0) {
+            return Ok(true);
+        }
+
+        // A multibyte sequence that was cut off at the end of the inspected bytes is not an
+        // indicator for binary data.
+        Ok(matches!(std::str::from_utf8(&head), Err(err) if err.error_len().is_some()))
+    }
 }
 
 impl Read for SourceReader {
@@ -241,6 +289,37 @@ mod test {
         ));
     }
 
+    #[test]
+    fn test_to_reader_with() {
+        use crate::fetch::{Fetched, Fetcher};
+
+        struct MockFetcher;
+
+        impl Fetcher for MockFetcher {
+            fn handles(&self, scheme: &str) -> bool {
+                scheme == "mock"
+            }
+
+            fn fetch(&self, _url: &Url) -> Result<Fetched> {
+                Ok(Fetched::new(Cursor::new("foo,bar\n")).with_encoding(Some(Encoding::Csv)))
+            }
+        }
+
+        let mut fetchers = Fetchers::new();
+        fetchers.register(MockFetcher);
+
+        let reader = Source::from("mock://host/data")
+            .to_reader_with(&fetchers)
+            .unwrap();
+        assert_eq!(reader.encoding(), Some(Encoding::Csv));
+
+        // The URL path takes precedence over the fetcher's encoding hint.
+        let reader = Source::from("mock://host/data.json")
+            .to_reader_with(&fetchers)
+            .unwrap();
+        assert_eq!(reader.encoding(), Some(Encoding::Json));
+    }
+
     #[test]
     fn test_source_reader() {
         let input = Cursor::new("---\nfoo: bar\n");
@@ -253,4 +332,24 @@ mod test {
 
         assert_eq!(&buf, "---\nfoo: bar\n");
     }
+
+    #[test]
+    fn test_source_reader_is_binary() {
+        let input = Cursor::new("{\"foo\": \"bär\"}\n");
+        let mut reader = SourceReader::new(Box::new(input), None).unwrap();
+        assert!(!reader.is_binary().unwrap());
+
+        let input = Cursor::new(vec![b'{', 0, 1, 2, b'\n', b'}']);
+        let mut reader = SourceReader::new(Box::new(input), None).unwrap();
+        assert!(reader.is_binary().unwrap());
+
+        let input = Cursor::new(vec![b'[', b'\n', 0xff, 0xfe, b']']);
+        let mut reader = SourceReader::new(Box::new(input), None).unwrap();
+        assert!(reader.is_binary().unwrap());
+
+        // Detection must not consume any input.
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, vec![b'[', b'\n', 0xff, 0xfe, b']']);
+    }
 }
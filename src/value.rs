@@ -21,8 +21,56 @@ pub trait ValueExt {
     /// not quoted.
     fn into_string(self) -> String;
 
+    /// Returns an iterator over the key-value pairs of a `Value::Object`. The iterator is empty
+    /// for all other variants.
+    ///
+    /// To access a single object field or array element, use `Value::get`, which accepts both
+    /// string keys and `usize` indices.
+    fn entries(&self) -> impl Iterator<Item = (&str, &Value)>;
+
+    /// Returns an iterator over the elements of a `Value::Array`. The iterator is empty for all
+    /// other variants.
+    fn iter(&self) -> impl Iterator<Item = &Value>;
+
     /// Deep merges `other` into `self`, replacing all values in `other` that were merged into
     /// `self` with `Value::Null`.
+    ///
+    /// The merge follows these rules:
+    ///
+    /// - Objects are merged recursively. Keys only present in `other` are added to `self`.
+    /// - Arrays are merged index by index. If `other` is longer, `self` is extended.
+    /// - `Value::Null` in `other` never overwrites a value in `self`.
+    /// - In all other cases the value from `other` replaces the value in `self`.
+    ///
+    /// ## Examples
+    ///
+    /// Objects are merged recursively, `null` values do not overwrite existing values:
+    ///
+    /// ```
+    /// use dts::value::ValueExt;
+    /// use serde_json::json;
+    ///
+    /// let mut value = json!({"foo": {"bar": 1, "baz": 2}, "qux": "a"});
+    /// let mut other = json!({"foo": {"baz": 3, "quux": 4}, "qux": null});
+    ///
+    /// value.deep_merge(&mut other);
+    ///
+    /// assert_eq!(value, json!({"foo": {"bar": 1, "baz": 3, "quux": 4}, "qux": "a"}));
+    /// ```
+    ///
+    /// Arrays are merged by index:
+    ///
+    /// ```
+    /// use dts::value::ValueExt;
+    /// use serde_json::json;
+    ///
+    /// let mut value = json!([{"foo": 1}, "bar"]);
+    /// let mut other = json!([{"baz": 2}, null, "qux"]);
+    ///
+    /// value.deep_merge(&mut other);
+    ///
+    /// assert_eq!(value, json!([{"foo": 1, "baz": 2}, "bar", "qux"]));
+    /// ```
     fn deep_merge(&mut self, other: &mut Value);
 }
 
@@ -51,6 +99,17 @@ impl ValueExt for Value {
         }
     }
 
+    fn entries(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.as_object()
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Value> {
+        self.as_array().into_iter().flatten()
+    }
+
     fn deep_merge(&mut self, other: &mut Value) {
         match (self, other) {
             (Value::Object(lhs), Value::Object(rhs)) => {
@@ -120,4 +179,30 @@ mod tests {
         assert_eq!(json!(1).into_string(), String::from("1"));
         assert_eq!(Value::Null.into_string(), String::from("null"));
     }
+
+    #[test]
+    fn test_entries() {
+        let value = json!({"foo": 1, "bar": [2]});
+        assert_eq!(
+            value.entries().collect::<Vec<_>>(),
+            vec![("foo", &json!(1)), ("bar", &json!([2]))]
+        );
+        assert_eq!(json!({}).entries().count(), 0);
+        assert_eq!(json!(["foo"]).entries().count(), 0);
+        assert_eq!(json!("foo").entries().count(), 0);
+        assert_eq!(Value::Null.entries().count(), 0);
+    }
+
+    #[test]
+    fn test_iter() {
+        let value = json!(["foo", {"bar": 1}]);
+        assert_eq!(
+            value.iter().collect::<Vec<_>>(),
+            vec![&json!("foo"), &json!({"bar": 1})]
+        );
+        assert_eq!(json!([]).iter().count(), 0);
+        assert_eq!(json!({"foo": "bar"}).iter().count(), 0);
+        assert_eq!(json!(1).iter().count(), 0);
+        assert_eq!(Value::Null.iter().count(), 0);
+    }
 }
@@ -0,0 +1,147 @@
+//! Conversions between YAML and JSON values which deal with YAML tags.
+//!
+//! JSON has no equivalent of YAML tags. Tagged YAML values are either converted into their
+//! untagged value or, if tags should be preserved, into an object of the form
+//! `{"__tag__": "!Tag", "value": <value>}` which is turned back into a tagged value when
+//! serializing to YAML.
+
+use crate::{Error, Result};
+use serde_json::{Map, Number, Value};
+use serde_yaml::value::{Tag, TaggedValue};
+
+/// The object key holding the tag of a tagged value.
+pub(crate) const TAG_KEY: &str = "__tag__";
+
+/// The object key holding the value of a tagged value.
+pub(crate) const VALUE_KEY: &str = "value";
+
+/// Converts a YAML value into a JSON value. Tags are dropped unless `preserve_tags` is `true`.
+pub(crate) fn to_json(value: serde_yaml::Value, preserve_tags: bool) -> Result<Value> {
+    let value = match value {
+        serde_yaml::Value::Null => Value::Null,
+        serde_yaml::Value::Bool(b) => Value::Bool(b),
+        serde_yaml::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::from(i)
+            } else if let Some(u) = n.as_u64() {
+                Value::from(u)
+            } else {
+                n.as_f64()
+                    .and_then(Number::from_f64)
+                    .map_or(Value::Null, Value::Number)
+            }
+        }
+        serde_yaml::Value::String(s) => Value::String(s),
+        serde_yaml::Value::Sequence(seq) => Value::Array(
+            seq.into_iter()
+                .map(|value| to_json(value, preserve_tags))
+                .collect::<Result<_>>()?,
+        ),
+        serde_yaml::Value::Mapping(mapping) => Value::Object(
+            mapping
+                .into_iter()
+                .map(|(key, value)| Ok((key_to_string(key)?, to_json(value, preserve_tags)?)))
+                .collect::<Result<_>>()?,
+        ),
+        serde_yaml::Value::Tagged(tagged) => {
+            let TaggedValue { tag, value } = *tagged;
+            let value = to_json(value, preserve_tags)?;
+
+            if preserve_tags {
+                let mut object = Map::with_capacity(2);
+                object.insert(TAG_KEY.into(), Value::String(tag.to_string()));
+                object.insert(VALUE_KEY.into(), value);
+                Value::Object(object)
+            } else {
+                value
+            }
+        }
+    };
+
+    Ok(value)
+}
+
+fn key_to_string(key: serde_yaml::Value) -> Result<String> {
+    match key {
+        serde_yaml::Value::String(s) => Ok(s),
+        serde_yaml::Value::Null => Ok(String::from("null")),
+        serde_yaml::Value::Bool(b) => Ok(b.to_string()),
+        serde_yaml::Value::Number(n) => Ok(n.to_string()),
+        serde_yaml::Value::Tagged(tagged) => key_to_string(tagged.value),
+        _ => Err(Error::new("YAML mapping keys must be scalar values")),
+    }
+}
+
+/// Converts a JSON value into a YAML value. Objects of the form
+/// `{"__tag__": "!Tag", "value": <value>}` are converted into tagged values.
+pub(crate) fn from_json(value: Value) -> serde_yaml::Value {
+    match value {
+        Value::Null => serde_yaml::Value::Null,
+        Value::Bool(b) => serde_yaml::Value::Bool(b),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                serde_yaml::Value::from(i)
+            } else if let Some(u) = n.as_u64() {
+                serde_yaml::Value::from(u)
+            } else {
+                serde_yaml::Value::from(n.as_f64().unwrap_or(f64::NAN))
+            }
+        }
+        Value::String(s) => serde_yaml::Value::String(s),
+        Value::Array(array) => {
+            serde_yaml::Value::Sequence(array.into_iter().map(from_json).collect())
+        }
+        Value::Object(mut object) => match (object.get(TAG_KEY), object.get(VALUE_KEY)) {
+            (Some(Value::String(tag)), Some(_)) if object.len() == 2 => {
+                let tag = Tag::new(tag.as_str());
+                let value = from_json(object.remove(VALUE_KEY).unwrap());
+                serde_yaml::Value::Tagged(Box::new(TaggedValue { tag, value }))
+            }
+            _ => serde_yaml::Value::Mapping(
+                object
+                    .into_iter()
+                    .map(|(key, value)| (serde_yaml::Value::String(key), from_json(value)))
+                    .collect(),
+            ),
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn test_to_json() {
+        let yaml: serde_yaml::Value =
+            serde_yaml::from_str("a: !Ref foo\nb: !GetAtt [x, y]\n1: !!str 2").unwrap();
+
+        assert_eq!(
+            to_json(yaml.clone(), false).unwrap(),
+            json!({"a": "foo", "b": ["x", "y"], "1": "2"})
+        );
+        assert_eq!(
+            to_json(yaml, true).unwrap(),
+            json!({
+                "a": {"__tag__": "!Ref", "value": "foo"},
+                "b": {"__tag__": "!GetAtt", "value": ["x", "y"]},
+                "1": "2"
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_json() {
+        let value = json!({
+            "a": {"__tag__": "!Ref", "value": "foo"},
+            "b": {"__tag__": "!Ref", "value": "foo", "extra": 1}
+        });
+
+        assert_eq!(
+            serde_yaml::to_string(&from_json(value)).unwrap(),
+            "a: !Ref foo\nb:\n  __tag__: '!Ref'\n  value: foo\n  extra: 1\n"
+        );
+    }
+}
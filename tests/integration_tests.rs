@@ -1,13 +1,22 @@
 use assert_cmd::Command;
 use predicates::prelude::*;
 use std::fs::read_to_string as read;
+use std::path::PathBuf;
+
+// Creates an empty temporary directory that is unique to the test and the test process.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dts-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
 
 #[test]
 fn json_to_yaml() {
     Command::cargo_bin("dts")
         .unwrap()
         .arg("tests/fixtures/example.json")
-        .args(&["-o", "yaml"])
+        .args(["-o", "yaml"])
         .assert()
         .success()
         .stdout(read("tests/fixtures/example.yaml").unwrap());
@@ -17,7 +26,7 @@ fn json_to_yaml() {
 fn json_to_yaml_stdin() {
     Command::cargo_bin("dts")
         .unwrap()
-        .args(&["-i", "json", "-o", "yaml"])
+        .args(["-i", "json", "-o", "yaml"])
         .pipe_stdin("tests/fixtures/example.json")
         .unwrap()
         .assert()
@@ -30,18 +39,37 @@ fn yaml_to_pretty_json() {
     Command::cargo_bin("dts")
         .unwrap()
         .arg("tests/fixtures/example.yaml")
-        .args(&["-o", "json", "-n"])
+        .args(["-o", "json", "-n", "--pretty"])
         .assert()
         .success()
         .stdout(read("tests/fixtures/example.json").unwrap());
 }
 
+#[test]
+fn compact_when_piped() {
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json"])
+        .write_stdin(r#"{"foo": ["bar"]}"#)
+        .assert()
+        .success()
+        .stdout(r#"{"foo":["bar"]}"#);
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "--pretty"])
+        .write_stdin(r#"{"foo": ["bar"]}"#)
+        .assert()
+        .success()
+        .stdout("{\n  \"foo\": [\n    \"bar\"\n  ]\n}");
+}
+
 #[test]
 fn json_to_toml() {
     Command::cargo_bin("dts")
         .unwrap()
         .arg("tests/fixtures/example.json")
-        .args(&["-o", "toml", "-c"])
+        .args(["-o", "toml", "-c"])
         .assert()
         .success()
         .stdout(read("tests/fixtures/example.toml").unwrap());
@@ -52,7 +80,7 @@ fn json_to_csv_filtered_flattened_with_keys() {
     Command::cargo_bin("dts")
         .unwrap()
         .arg("tests/fixtures/example.json")
-        .args(&["-o", "csv", "-j", ".users[].friends[]", "-K"])
+        .args(["-o", "csv", "-j", ".users[].friends[]", "-K"])
         .assert()
         .success()
         .stdout(read("tests/fixtures/friends.csv").unwrap());
@@ -63,7 +91,7 @@ fn json_to_csv_collections_as_json() {
     Command::cargo_bin("dts")
         .unwrap()
         .arg("tests/fixtures/example.json")
-        .args(&["-o", "csv", "-j", ".users[]", "-K"])
+        .args(["-o", "csv", "-j", ".users[]", "-K"])
         .assert()
         .success()
         .stdout(read("tests/fixtures/users.csv").unwrap());
@@ -74,7 +102,7 @@ fn json_to_gron() {
     Command::cargo_bin("dts")
         .unwrap()
         .arg("tests/fixtures/example.json")
-        .args(&["-o", "gron"])
+        .args(["-o", "gron"])
         .assert()
         .success()
         .stdout(read("tests/fixtures/example.js").unwrap());
@@ -85,7 +113,7 @@ fn json_to_hcl() {
     Command::cargo_bin("dts")
         .unwrap()
         .arg("tests/fixtures/example.json")
-        .args(&["-o", "hcl"])
+        .args(["-o", "hcl", "--pretty"])
         .assert()
         .success()
         .stdout(read("tests/fixtures/example.hcl").unwrap());
@@ -96,7 +124,7 @@ fn json_to_hcl_compact() {
     Command::cargo_bin("dts")
         .unwrap()
         .arg("tests/fixtures/example.json")
-        .args(&["-o", "hcl", "--compact"])
+        .args(["-o", "hcl", "--compact"])
         .assert()
         .success()
         .stdout(read("tests/fixtures/example.compact.hcl").unwrap());
@@ -107,7 +135,7 @@ fn hcl_to_json() {
     Command::cargo_bin("dts")
         .unwrap()
         .arg("tests/fixtures/math.hcl")
-        .args(&["-o", "json"])
+        .args(["-o", "json", "--pretty"])
         .assert()
         .success()
         .stdout(read("tests/fixtures/math.json").unwrap());
@@ -118,7 +146,7 @@ fn hcl_to_json_simplified() {
     Command::cargo_bin("dts")
         .unwrap()
         .arg("tests/fixtures/math.hcl")
-        .args(&["-o", "json", "--simplify"])
+        .args(["-o", "json", "--simplify", "--pretty"])
         .assert()
         .success()
         .stdout(read("tests/fixtures/math.simplified.json").unwrap());
@@ -129,7 +157,7 @@ fn gron_to_json() {
     Command::cargo_bin("dts")
         .unwrap()
         .arg("tests/fixtures/example.js")
-        .args(&["-i", "gron", "-n", "-j", ".json"])
+        .args(["-i", "gron", "-n", "-j", ".json", "--pretty"])
         .assert()
         .success()
         .stdout(read("tests/fixtures/example.js.ungron.json").unwrap());
@@ -162,7 +190,7 @@ fn encoding_inferred_from_first_line() {
 fn multiple_sinks_require_array() {
     Command::cargo_bin("dts")
         .unwrap()
-        .args(&["-i", "json", "-O", "-", "-O", "-"])
+        .args(["-i", "json", "-O", "-", "-O", "-"])
         .write_stdin("{}")
         .assert()
         .failure()
@@ -188,7 +216,12 @@ fn merge_json() {
     Command::cargo_bin("dts")
         .unwrap()
         .arg("tests/fixtures/example.json")
-        .args(&["-j", "reduce .users[] as $item ({}; . + $item)", "-n"])
+        .args([
+            "-j",
+            "reduce .users[] as $item ({}; . + $item)",
+            "-n",
+            "--pretty",
+        ])
         .assert()
         .success()
         .stdout(read("tests/fixtures/example.merged.json").unwrap());
@@ -199,7 +232,7 @@ fn filter_expression_from_file() {
     Command::cargo_bin("dts")
         .unwrap()
         .arg("tests/fixtures/example.json")
-        .args(&["-j", "@tests/fixtures/filter.jq", "-n"])
+        .args(["-j", "@tests/fixtures/filter.jq", "-n", "--pretty"])
         .assert()
         .success()
         .stdout(read("tests/fixtures/example.filtered.json").unwrap());
@@ -213,7 +246,7 @@ fn continue_on_error() {
         .unwrap()
         .arg("tests/fixtures/example.js")
         .arg("tests/fixtures/example.json")
-        .args(&[
+        .args([
             "-i",
             "json",
             "-j",
@@ -227,15 +260,869 @@ fn continue_on_error() {
         .unwrap()
         .arg("tests/fixtures/example.js")
         .arg("tests/fixtures/example.json")
-        .args(&[
+        .args([
             "-i",
             "json",
             "-j",
             ".[] | reduce .users[] as $item ({}; . + $item)",
             "-n",
             "--continue-on-error",
+            "--pretty",
         ])
         .assert()
         .success()
         .stdout(read("tests/fixtures/example.merged.json").unwrap());
 }
+
+#[test]
+fn assert_output() {
+    Command::cargo_bin("dts")
+        .unwrap()
+        .arg("tests/fixtures/example.json")
+        .args(["-o", "yaml", "--assert", "tests/fixtures/example.yaml"])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .arg("tests/fixtures/example.json")
+        .args(["-j", ".users[0]", "--assert", "tests/fixtures/example.json"])
+        .assert()
+        .failure()
+        .stdout("")
+        .stderr(predicate::str::contains(
+            "output does not match `tests/fixtures/example.json`",
+        ))
+        .stderr(predicate::str::contains("--- tests/fixtures/example.json"))
+        .stderr(predicate::str::contains("+++ <output>"));
+}
+
+#[test]
+fn partition_output() {
+    let dir = temp_dir("partition-output");
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "--partition-by", "region", "--partition-dir"])
+        .arg(&dir)
+        .write_stdin(
+            r#"[
+                {"id": 1, "region": "eu/west"},
+                {"id": 2, "region": "us"},
+                {"id": 3, "region": "eu/west"},
+                {"id": 4}
+            ]"#,
+        )
+        .assert()
+        .success()
+        .stdout("");
+
+    let read_json = |name: &str| -> serde_json::Value {
+        serde_json::from_str(&read(dir.join(name)).unwrap()).unwrap()
+    };
+
+    assert_eq!(
+        read_json("eu_west.json"),
+        serde_json::json!([{"id": 1, "region": "eu/west"}, {"id": 3, "region": "eu/west"}])
+    );
+    assert_eq!(
+        read_json("us.json"),
+        serde_json::json!([{"id": 2, "region": "us"}])
+    );
+    assert_eq!(read_json("null.json"), serde_json::json!([{"id": 4}]));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn partition_output_collision() {
+    let dir = temp_dir("partition-output-collision");
+
+    for (input, values) in [
+        (r#"[{"r": "a/b"}, {"r": "a?b"}]"#, r#"`"a/b"` and `"a?b"`"#),
+        (r#"[{"r": 1}, {"r": "1"}]"#, r#"`1` and `"1"`"#),
+    ] {
+        Command::cargo_bin("dts")
+            .unwrap()
+            .args(["-i", "json", "--partition-by", "r", "--partition-dir"])
+            .arg(&dir)
+            .write_stdin(input)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(format!(
+                "partition values {values} of field `r` both map to output file"
+            )));
+    }
+
+    // Nothing is written if partitions collide.
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn atomic_output() {
+    let dir = temp_dir("atomic-output");
+
+    let path = dir.join("out.toml");
+    std::fs::write(&path, "foo = \"bar\"\n").unwrap();
+
+    // A top-level array cannot be serialized to TOML.
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "--overwrite", "--atomic", "-O"])
+        .arg(&path)
+        .write_stdin("[1, 2]")
+        .assert()
+        .failure();
+
+    assert_eq!(read(&path).unwrap(), "foo = \"bar\"\n");
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "--overwrite", "--atomic", "-O"])
+        .arg(&path)
+        .write_stdin(r#"{"foo": "baz"}"#)
+        .assert()
+        .success();
+
+    assert_eq!(read(&path).unwrap(), "foo = \"baz\"\n");
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+    // The replaced file keeps its permissions.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+
+        Command::cargo_bin("dts")
+            .unwrap()
+            .args(["-i", "json", "--overwrite", "--atomic", "-O"])
+            .arg(&path)
+            .write_stdin(r#"{"foo": "qux"}"#)
+            .assert()
+            .success();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn with_source_meta() {
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args([
+            "tests/fixtures/example.json",
+            "tests/fixtures/example.toml",
+            "--with-source-meta",
+            "-j",
+            "map(.source)",
+            "-c",
+        ])
+        .assert()
+        .success()
+        .stdout(r#"["tests/fixtures/example.json","tests/fixtures/example.toml"]"#);
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args([
+            "tests/fixtures/example.json",
+            "tests/fixtures/example.toml",
+            "--with-source-meta",
+            "--file-paths",
+            "-j",
+            "to_entries | map({key, source: .value.source, data: (.value.data | type)})",
+            "-c",
+        ])
+        .assert()
+        .success()
+        .stdout(concat!(
+            r#"[{"key":"tests/fixtures/example.json","source":"tests/fixtures/example.json","data":"object"},"#,
+            r#"{"key":"tests/fixtures/example.toml","source":"tests/fixtures/example.toml","data":"object"}]"#
+        ));
+}
+
+#[test]
+fn multiple_filter_expressions() {
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args([
+            "-i", "json", "-j", ".users", "-j", "map(.id)", "-j", "length",
+        ])
+        .write_stdin(r#"{"users": [{"id": 1}, {"id": 2}]}"#)
+        .assert()
+        .success()
+        .stdout("2");
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "-j", ".users", "-j", "map(.id"])
+        .write_stdin(r#"{"users": [{"id": 1}, {"id": 2}]}"#)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid jq expression #2"));
+}
+
+#[test]
+fn shard_output() {
+    let dir = temp_dir("shard-output");
+
+    // Each compact shard of `n` elements is `7n + 1` bytes long, so at most 4 elements fit into
+    // 30 bytes.
+    let input = r#"["aaaa","aaaa","aaaa","aaaa","aaaa","aaaa","aaaa","aaaa","aaaa","aaaa"]"#;
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "-c", "--shard-max-bytes", "30", "-O"])
+        .arg(dir.join("out.json"))
+        .write_stdin(input)
+        .assert()
+        .success();
+
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 3);
+    assert_eq!(
+        read(dir.join("out.0.json")).unwrap(),
+        r#"["aaaa","aaaa","aaaa","aaaa"]"#
+    );
+    assert_eq!(
+        read(dir.join("out.1.json")).unwrap(),
+        r#"["aaaa","aaaa","aaaa","aaaa"]"#
+    );
+    assert_eq!(read(dir.join("out.2.json")).unwrap(), r#"["aaaa","aaaa"]"#);
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args([
+            "-i",
+            "json",
+            "-c",
+            "--shard-max-bytes",
+            "5",
+            "--overwrite",
+            "-O",
+        ])
+        .arg(dir.join("out.json"))
+        .write_stdin(input)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "array element at index 0 exceeds the shard size limit of 5 bytes",
+        ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn shard_output_many_elements() {
+    let dir = temp_dir("shard-output-many-elements");
+
+    let input = serde_json::to_string(
+        &(0..20_000)
+            .map(|id| serde_json::json!({"name": format!("user{id:05}")}))
+            .collect::<Vec<_>>(),
+    )
+    .unwrap();
+
+    // Sharding must not re-serialize the whole shard for every element it holds.
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "-c", "--shard-max-bytes", "100000000", "-O"])
+        .arg(dir.join("out.json"))
+        .write_stdin(input.clone())
+        .timeout(std::time::Duration::from_secs(30))
+        .assert()
+        .success();
+
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    assert_eq!(read(dir.join("out.0.json")).unwrap(), input);
+
+    // All elements have the same size, so splitting the input in the middle produces two shards of
+    // 10k elements each.
+    let max_bytes = input.len() / 2 + 1;
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "-c", "--overwrite", "--shard-max-bytes"])
+        .arg(max_bytes.to_string())
+        .arg("-O")
+        .arg(dir.join("out.json"))
+        .write_stdin(input)
+        .assert()
+        .success();
+
+    let shards: Vec<serde_json::Value> = (0..2)
+        .map(|i| serde_json::from_str(&read(dir.join(format!("out.{i}.json"))).unwrap()).unwrap())
+        .collect();
+
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+    assert!(shards
+        .iter()
+        .all(|shard| shard.as_array().unwrap().len() == 10_000));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn shard_output_position_dependent_size() {
+    let dir = temp_dir("shard-output-position-dependent-size");
+
+    // The size of gron lines grows with the number of digits of the array index.
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args([
+            "-i",
+            "json",
+            "-o",
+            "gron",
+            "--shard-max-bytes",
+            "1000",
+            "-O",
+        ])
+        .arg(dir.join("out.gron"))
+        .write_stdin(serde_json::to_string(&vec!["x"; 200]).unwrap())
+        .assert()
+        .success();
+
+    let shards: Vec<String> = (0..std::fs::read_dir(&dir).unwrap().count())
+        .map(|i| read(dir.join(format!("out.{i}.gron"))).unwrap())
+        .collect();
+
+    assert!(shards.len() > 1);
+    assert!(shards.iter().all(|shard| shard.len() <= 1000));
+    assert_eq!(
+        shards
+            .iter()
+            .map(|shard| shard.matches(" = \"x\";").count())
+            .sum::<usize>(),
+        200
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn trace_transforms() {
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "-j", ".a", "-j", "map(. * 2)", "-c"])
+        .arg("--trace-transforms")
+        .write_stdin(r#"{"a": [1, 2]}"#)
+        .assert()
+        .success()
+        .stdout("[2,4]")
+        .stderr("[jq #1 `.a`] [1,2]\n[jq #2 `map(. * 2)`] [2,4]\n");
+}
+
+#[test]
+fn preserve_yaml_tags() {
+    Command::cargo_bin("dts")
+        .unwrap()
+        .arg("tests/fixtures/cloudformation.yaml")
+        .args(["--preserve-yaml-tags", "-o", "yaml"])
+        .assert()
+        .success()
+        .stdout(read("tests/fixtures/cloudformation.yaml").unwrap());
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .arg("tests/fixtures/cloudformation.yaml")
+        .args([
+            "--preserve-yaml-tags",
+            "-j",
+            ".Outputs.BucketName.Value",
+            "-c",
+        ])
+        .assert()
+        .success()
+        .stdout(r#"{"__tag__":"!Ref","value":"Bucket"}"#);
+}
+
+#[test]
+fn no_detect() {
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["tests/fixtures/example.json", "--no-detect"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--input-encoding"));
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["tests/fixtures/example.json", "--no-detect", "-i", "json"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--output-encoding"));
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args([
+            "tests/fixtures/example.json",
+            "--no-detect",
+            "-i",
+            "json",
+            "-o",
+            "json",
+        ])
+        .assert()
+        .success();
+}
+
+#[test]
+fn manifest() {
+    let dir = temp_dir("manifest");
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "-c", "--manifest"])
+        .arg(dir.join("manifest.json"))
+        .arg("-O")
+        .arg(dir.join("a.json"))
+        .arg("-O")
+        .arg(dir.join("b.yaml"))
+        .write_stdin(r#"[{"a":1},{"b":2}]"#)
+        .assert()
+        .success();
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&read(dir.join("manifest.json")).unwrap()).unwrap();
+
+    let bytes = |name: &str| std::fs::metadata(dir.join(name)).unwrap().len();
+
+    assert_eq!(
+        manifest,
+        serde_json::json!([
+            {"path": dir.join("a.json"), "bytes": bytes("a.json"), "encoding": "json"},
+            {"path": dir.join("b.yaml"), "bytes": bytes("b.yaml"), "encoding": "yaml"}
+        ])
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn jobs() {
+    let output = Command::cargo_bin("dts")
+        .unwrap()
+        .args(["tests/fixtures/example.json", "tests/fixtures/example.yaml"])
+        .args(["tests/fixtures/example.toml", "-c"])
+        .output()
+        .unwrap();
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["tests/fixtures/example.json", "tests/fixtures/example.yaml"])
+        .args(["tests/fixtures/example.toml", "-c", "--jobs", "1"])
+        .assert()
+        .success()
+        .stdout(output.stdout);
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["tests/fixtures/example.json", "--jobs", "0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "invalid value '0' for '--jobs <N>'",
+        ));
+}
+
+#[test]
+fn config_file() {
+    let dir = temp_dir("config-file");
+
+    let config = dir.join("config.toml");
+    std::fs::write(&config, "output_encoding = \"yaml\"\ncompact = true\n").unwrap();
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "--config"])
+        .arg(&config)
+        .write_stdin(r#"{"foo": ["bar"]}"#)
+        .assert()
+        .success()
+        .stdout("---\nfoo:\n- bar\n");
+
+    // Options from the command line take precedence, also over conflicting config options.
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "-o", "json", "--pretty", "--config"])
+        .arg(&config)
+        .write_stdin(r#"{"foo": ["bar"]}"#)
+        .assert()
+        .success()
+        .stdout("{\n  \"foo\": [\n    \"bar\"\n  ]\n}");
+
+    // The conflict is declared on `--compact`, but must also be honored if the config file
+    // contains `--pretty`.
+    let config = dir.join("pretty.yaml");
+    std::fs::write(&config, "pretty: true\n").unwrap();
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "-c", "--config"])
+        .arg(&config)
+        .write_stdin(r#"{"foo": ["bar"]}"#)
+        .assert()
+        .success()
+        .stdout(r#"{"foo":["bar"]}"#);
+
+    let config = dir.join("config.json");
+    std::fs::write(
+        &config,
+        r#"{"output-encoding": "json", "jq": [".foo", ".[0]"]}"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "--config"])
+        .arg(&config)
+        .write_stdin(r#"{"foo": ["bar"]}"#)
+        .assert()
+        .success()
+        .stdout(r#""bar""#);
+
+    std::fs::write(&config, r#"{"unknown": true}"#).unwrap();
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "--config"])
+        .arg(&config)
+        .write_stdin("{}")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "unknown option `unknown` in config file",
+        ));
+
+    // The config file can provide options that are required by options on the command line.
+    let config = dir.join("partition.toml");
+    std::fs::write(
+        &config,
+        format!("partition_dir = {:?}\n", dir.join("partitions")),
+    )
+    .unwrap();
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "--partition-by", "r", "--config"])
+        .arg(&config)
+        .write_stdin(r#"[{"r": "a"}, {"r": "b"}]"#)
+        .assert()
+        .success();
+
+    assert!(dir.join("partitions/a.json").exists());
+    assert!(dir.join("partitions/b.json").exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn binary_input() {
+    let binary = vec![0x7f, b'E', b'L', b'F', 0x02, 0x01, 0x00, 0x00, 0xff, 0xfe];
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json"])
+        .write_stdin(binary.clone())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("appears to be binary"));
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "--allow-binary"])
+        .write_stdin(binary)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("failed to deserialize"));
+}
+
+#[test]
+fn append() {
+    let dir = temp_dir("append");
+
+    for record in [r#"{"n":1}"#, r#"{"n":2}"#] {
+        Command::cargo_bin("dts")
+            .unwrap()
+            .args(["-i", "json", "-c", "-n", "--append", "-O"])
+            .arg(dir.join("out.json"))
+            .write_stdin(record)
+            .assert()
+            .success();
+    }
+
+    assert_eq!(
+        read(dir.join("out.json")).unwrap(),
+        "{\"n\":1}\n{\"n\":2}\n"
+    );
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .arg(dir.join("out.json"))
+        .assert()
+        .success()
+        .stdout(r#"[{"n":1},{"n":2}]"#);
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "--append", "--atomic", "-O"])
+        .arg(dir.join("out.json"))
+        .write_stdin("{}")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn emit_schema() {
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "--emit-schema"])
+        .write_stdin(r#"{"users": [{"id": 1, "name": "a"}, {"id": 2}]}"#)
+        .assert()
+        .success()
+        .stdout(concat!(
+            r#"{"$schema":"https://json-schema.org/draft/2020-12/schema","type":"object","#,
+            r#""properties":{"users":{"type":"array","items":{"type":"object","properties":"#,
+            r#"{"id":{"type":"integer"},"name":{"type":"string"}},"required":["id"]}}},"#,
+            r#""required":["users"]}"#
+        ));
+}
+
+#[test]
+fn diff_with() {
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "--color", "never", "--diff-with"])
+        .arg("tests/fixtures/example.json")
+        .write_stdin(read("tests/fixtures/example.json").unwrap())
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["tests/fixtures/example.json", "--color", "never"])
+        .args(["-j", ".users[0].age = 99", "--diff-with"])
+        .arg("tests/fixtures/example.json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "--- tests/fixtures/example.json\n+++ <output>\n",
+        ))
+        .stdout(predicate::str::contains(
+            "\n-      \"age\": 34,\n+      \"age\": 99,\n",
+        ));
+}
+
+#[test]
+#[cfg(feature = "color")]
+fn diff_with_color() {
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["tests/fixtures/example.json", "--color", "always"])
+        .args(["-j", ".users[0].age = 99", "--diff-with"])
+        .arg("tests/fixtures/example.json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[31m-      \"age\": 34,"))
+        .stdout(predicate::str::contains("\x1b[32m+      \"age\": 99,"));
+}
+
+#[test]
+fn stream() {
+    let dir = temp_dir("stream");
+
+    let input: String = (0..1000)
+        .map(|n| format!("{{\"n\":{n},\"tags\":[\"a\"]}}\n"))
+        .collect();
+    std::fs::write(dir.join("in.json"), input).unwrap();
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .arg(dir.join("in.json"))
+        .args(["--stream", "-j", "{m: (.n * 2)}", "-O"])
+        .arg(dir.join("out.json"))
+        .assert()
+        .success();
+
+    let output = read(dir.join("out.json")).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+
+    assert_eq!(lines.len(), 1000);
+    assert_eq!(lines[0], r#"{"m":0}"#);
+    assert_eq!(lines[999], r#"{"m":1998}"#);
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "--stream", "--jcs"])
+        .write_stdin("{\"b\":1,\"a\":2.50}\n{\"d\":[],\"c\":null}\n")
+        .assert()
+        .success()
+        .stdout("{\"a\":2.5,\"b\":1}\n{\"c\":null,\"d\":[]}\n");
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .arg(dir.join("in.json"))
+        .args(["--stream", "-o", "yaml"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--stream only supports JSON input and output",
+        ));
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .arg(dir.join("in.json"))
+        .args(["--stream", "--atomic", "--overwrite", "-O"])
+        .arg(dir.join("out.json"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "--stream"])
+        .write_stdin(vec![b'{', 0, 0, b'}'])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("appears to be binary"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn stream_bounded_memory() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::process::Stdio;
+
+    // Each record is about 1 KiB, so the whole input is about 64 MiB.
+    const RECORDS: usize = 65_536;
+    const MAX_RSS_KIB: usize = 32 * 1024;
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("dts"))
+        .args(["-i", "json", "--stream", "-j", ".n"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    let writer = std::thread::spawn(move || {
+        let padding = "x".repeat(1000);
+        for n in 0..RECORDS {
+            writeln!(stdin, r#"{{"n":{n},"padding":"{padding}"}}"#).unwrap();
+        }
+        // Keep stdin open so that the process stays alive until its memory usage was read.
+        stdin
+    });
+
+    let mut lines = BufReader::new(child.stdout.take().unwrap()).lines();
+
+    // The last few lines may still be buffered until stdin is closed.
+    for n in 0..RECORDS - 2000 {
+        assert_eq!(lines.next().unwrap().unwrap(), n.to_string());
+    }
+
+    let status = std::fs::read_to_string(format!("/proc/{}/status", child.id())).unwrap();
+    let max_rss_kib: usize = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))
+        .and_then(|value| value.trim().trim_end_matches("kB").trim().parse().ok())
+        .unwrap();
+
+    drop(writer.join().unwrap());
+
+    assert_eq!(lines.count(), 2000);
+    assert!(child.wait().unwrap().success());
+    assert!(
+        max_rss_kib < MAX_RSS_KIB,
+        "peak memory usage of {max_rss_kib} KiB exceeds {MAX_RSS_KIB} KiB"
+    );
+}
+
+#[test]
+fn assert_output_shell() {
+    let dir = temp_dir("assert-output-shell");
+    let golden = dir.join("golden.sh");
+    std::fs::write(&golden, "FOO_BAR='baz'\n").unwrap();
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "-o", "shell", "--assert"])
+        .arg(&golden)
+        .write_stdin(r#"{"foo": {"bar": "baz"}}"#)
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("dts")
+        .unwrap()
+        .args(["-i", "json", "-o", "shell", "--assert"])
+        .arg(&golden)
+        .write_stdin(r#"{"foo": {"bar": "qux"}}"#)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("-FOO_BAR='baz'\n+FOO_BAR='qux'"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn assert_output_gron_quote() {
+    let dir = temp_dir("assert-output-gron-quote");
+    let golden = dir.join("golden.js");
+
+    for (quote, expected) in [
+        ("single", "json = {};\njson.foo = 'it'\\''s';\n"),
+        ("none", "json = {};\njson.foo = it's;\n"),
+    ] {
+        std::fs::write(&golden, expected).unwrap();
+
+        Command::cargo_bin("dts")
+            .unwrap()
+            .args([
+                "-i",
+                "json",
+                "-o",
+                "gron",
+                "--gron-quote",
+                quote,
+                "--assert",
+            ])
+            .arg(&golden)
+            .write_stdin(r#"{"foo": "it's"}"#)
+            .assert()
+            .success()
+            .stdout("");
+
+        Command::cargo_bin("dts")
+            .unwrap()
+            .args([
+                "-i",
+                "json",
+                "-o",
+                "gron",
+                "--gron-quote",
+                quote,
+                "--assert",
+            ])
+            .arg(&golden)
+            .write_stdin(r#"{"foo": "bar"}"#)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("output does not match"));
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}